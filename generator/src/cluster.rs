@@ -1,11 +1,15 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
+use convert_case::{Case, Casing};
+
 use svd_parser::{Cluster, Register, RegisterProperties};
 
 use crate::overrides::{ClusterOverrides, RegisterOverrides};
 use crate::register::_Register;
-use crate::utils::{array_names, build_children, build_ident, merge_defaults};
+use crate::utils::{
+    array_names, build_children, build_ident, indent, merge_defaults, BusProperties, Child, Gate,
+};
 
 #[derive(Clone)]
 pub(super) struct _Cluster<'a> {
@@ -15,6 +19,8 @@ pub(super) struct _Cluster<'a> {
     offset: u64,
     pub(super) clusters: Vec<_Cluster<'a>>,
     pub(super) registers: Vec<_Register<'a>>,
+    pub(super) order: Vec<Child>,
+    gate: Option<Gate>,
 }
 
 impl<'a> _Cluster<'a> {
@@ -23,6 +29,7 @@ impl<'a> _Cluster<'a> {
         clusters: &HashMap<String, &'a Cluster>,
         registers: &HashMap<String, &'a Register>,
         defaults: RegisterProperties,
+        bus: BusProperties<'a>,
         cluster_overrides: Option<&'a HashMap<String, ClusterOverrides>>,
         register_overrides: Option<&'a HashMap<String, RegisterOverrides>>,
     ) -> Vec<_Cluster<'a>> {
@@ -31,6 +38,7 @@ impl<'a> _Cluster<'a> {
             clusters,
             registers,
             defaults,
+            bus,
             cluster_overrides,
             register_overrides,
         );
@@ -45,9 +53,11 @@ impl<'a> _Cluster<'a> {
                         offset,
                         clusters: _cluster.clusters.clone(),
                         registers: _cluster.registers.clone(),
+                        order: _cluster.order.clone(),
+                        gate: None,
                         .._cluster
                     });
-                    offset = offset + dim.dim_increment as u64;
+                    offset += dim.dim_increment as u64;
                 }
                 clusters
             }
@@ -59,15 +69,17 @@ impl<'a> _Cluster<'a> {
         clusters: &HashMap<String, &'a Cluster>,
         registers: &HashMap<String, &'a Register>,
         defaults: RegisterProperties,
+        bus: BusProperties<'a>,
         cluster_overrides: Option<&'a HashMap<String, ClusterOverrides>>,
         register_overrides: Option<&'a HashMap<String, RegisterOverrides>>,
     ) -> _Cluster<'a> {
         let defaults = merge_defaults(cluster.default_register_properties(clusters), defaults);
-        let (clusters, registers) = build_children(
+        let (clusters, registers, order) = build_children(
             &cluster.children,
             clusters,
             registers,
             defaults,
+            bus,
             cluster_overrides,
             register_overrides,
         );
@@ -79,8 +91,19 @@ impl<'a> _Cluster<'a> {
             offset: cluster.address_offset as u64,
             clusters,
             registers,
+            order,
+            gate: None,
         }
     }
+
+    /// Makes cluster registers obtainable only via peripheral clock token
+    pub(super) fn gate(&mut self, token: String, prefix: &str) {
+        let prefix = format!("{}{}_", prefix, self.name);
+        for cluster in &mut self.clusters {
+            cluster.gate(format!("super::{}", token), &prefix);
+        }
+        self.gate = Some(Gate { token, prefix });
+    }
 }
 
 impl<'a> Display for _Cluster<'a> {
@@ -94,10 +117,27 @@ impl<'a> Display for _Cluster<'a> {
         }
         write!(
             f,
-            "const BASE_ADDRESS: usize = super::BASE_ADDRESS + {offset:#X};\n\n",
+            "pub const BASE_ADDRESS: usize = super::BASE_ADDRESS + {offset:#X};\n\n",
             offset = self.offset
         )?;
-        write_children!(self, f);
+        match &self.gate {
+            Some(gate) => {
+                write_gated_children!(self, f, gate.token, gate.prefix);
+            }
+            None => {
+                write_children!(self, f);
+            }
+        }
+        write_reset_all!(self, f, self.gate.as_ref().map(|gate| &gate.token));
+        write!(
+            f,
+            "\npub const METADATA: ral::ClusterMetadata = ral::ClusterMetadata {{\n    name: \"{name}\",\n",
+            name = self.name
+        )?;
+        write_optional_str!(f, "description", self.description);
+        writeln!(f, "    address: BASE_ADDRESS,")?;
+        write_children_metadata!(self, f);
+        writeln!(f, "}};")?;
         Ok(())
     }
 }
@@ -4,7 +4,7 @@ use svd_parser::Device;
 
 use crate::overrides::DeviceOverrides;
 use crate::peripheral::_Peripherals;
-use crate::utils::build_ident;
+use crate::utils::{build_ident, indent};
 
 pub(super) struct _Device<'a> {
     pub(super) name: String,
@@ -13,26 +13,62 @@ pub(super) struct _Device<'a> {
 }
 
 impl<'a> _Device<'a> {
-    pub(super) fn build(device: &'a Device, overrides: Option<&'a DeviceOverrides>) -> _Device<'a> {
-        _Device {
+    pub(super) fn build(
+        device: &'a Device,
+        overrides: Option<&'a DeviceOverrides>,
+    ) -> anyhow::Result<_Device<'a>> {
+        Ok(_Device {
             name: device.name(overrides),
             description: device.description(overrides),
-            peripherals: _Peripherals::build(device, overrides),
-        }
+            peripherals: _Peripherals::build(device, overrides)?,
+        })
     }
 }
 
 impl<'a> Display for _Device<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "#![no_std]\n")?;
+        writeln!(f, "#![no_std]")?;
         if let Some(description) = self.description {
-            write!(
+            writeln!(f, "#![doc = \"{description}\"]", description = description)?;
+        }
+        writeln!(f, "\npub mod peripherals;")?;
+        write!(
+            f,
+            "\npub const METADATA: ral::DeviceMetadata = ral::DeviceMetadata {{\n    name: \"{name}\",\n",
+            name = self.name
+        )?;
+        write_optional_str!(f, "description", self.description);
+        writeln!(f, "    peripherals: &[")?;
+        for peripheral in self.peripherals.iter() {
+            if let Some(features_cfg) = features_cfg!(peripheral) {
+                write!(f, "{}", indent(features_cfg, 2))?;
+            }
+            writeln!(
+                f,
+                "        peripherals::{module}::METADATA,",
+                module = &peripheral.name
+            )?;
+        }
+        writeln!(f, "    ],\n}};")?;
+        writeln!(
+            f,
+            "\n/// Writes reset values to writable registers of all peripherals in SVD order, skipping clock gated\n/// peripherals, which are reset with their own `reset_all(&clock)` once clock is enabled\n///\n/// Fails on the first register borrowed elsewhere, registers written before it stay reset\npub fn reset_all() -> Result<(), ral::BorrowError> {{"
+        )?;
+        for peripheral in self
+            .peripherals
+            .iter()
+            .filter(|peripheral| peripheral.clock.is_none())
+        {
+            if let Some(features_cfg) = features_cfg!(peripheral) {
+                write!(f, "{}", indent(features_cfg, 1))?;
+            }
+            writeln!(
                 f,
-                "#![doc = \"{description}\"]\n",
-                description = description
+                "    peripherals::{module}::reset_all()?;",
+                module = &peripheral.name
             )?;
         }
-        write!(f, "\npub mod peripherals;")
+        writeln!(f, "    Ok(())\n}}")
     }
 }
 
@@ -1,30 +1,52 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
-use svd_parser::{Access, Field};
+use svd_parser::{Access, Field, ModifiedWriteValues};
 
 use crate::overrides::FieldOverrides;
 use crate::utils::{array_names, build_ident};
 
 #[derive(Clone)]
 pub(super) struct _Field<'a> {
-    name: String,
+    pub(super) name: String,
     description: Option<&'a String>,
     offset: u32,
     width: u32,
     access: Option<Access>,
+    modified_write_values: Option<ModifiedWriteValues>,
     ty: &'a str,
+    /// Count and step of field array
+    dim: Option<(u32, u32)>,
+    scaling: Option<&'a FieldOverrides>,
 }
 
 impl<'a> _Field<'a> {
+    /// Whether field can be written, read-only fields aren't
+    pub(super) fn is_writable(&self) -> bool {
+        self.access != Some(Access::ReadOnly)
+    }
+
+    /// Whether field is a single bit, not a field array
+    pub(super) fn is_flag(&self) -> bool {
+        self.width == 1 && self.dim.is_none()
+    }
+
     pub(super) fn build_all(
         field: &'a Field,
         fields: &HashMap<String, &Field>,
+        modified_write_values: Option<ModifiedWriteValues>,
         overrides: Option<&'a HashMap<String, FieldOverrides>>,
     ) -> Vec<_Field<'a>> {
-        let _field = _Field::build(field, fields, overrides);
+        let _field = _Field::build(field, fields, modified_write_values, overrides);
         match field {
             Field::Single(_) => vec![_field],
+            Field::Array(_, dim) if dim.dim_index.is_none() && _field.name.contains("%s") => {
+                vec![_Field {
+                    name: build_ident(&_field.name.replace("[%s]", "").replace("%s", "")),
+                    dim: Some((dim.dim, dim.dim_increment)),
+                    .._field
+                }]
+            }
             Field::Array(_, dim) => {
                 let mut fields = Vec::new();
                 let mut offset = _field.offset;
@@ -34,7 +56,7 @@ impl<'a> _Field<'a> {
                         offset,
                         .._field
                     });
-                    offset = offset + dim.dim_increment;
+                    offset += dim.dim_increment;
                 }
                 fields
             }
@@ -44,6 +66,7 @@ impl<'a> _Field<'a> {
     pub(super) fn build(
         field: &'a Field,
         fields: &HashMap<String, &Field>,
+        modified_write_values: Option<ModifiedWriteValues>,
         overrides: Option<&'a HashMap<String, FieldOverrides>>,
     ) -> _Field<'a> {
         let overrides = field.overrides(overrides);
@@ -55,7 +78,12 @@ impl<'a> _Field<'a> {
             offset: range.offset,
             width,
             access: field.access(fields),
+            modified_write_values: field
+                .modified_write_values(fields)
+                .or(modified_write_values),
             ty: field.ty(overrides),
+            dim: None,
+            scaling: overrides.filter(|overrides| overrides.is_scaled()),
         }
     }
 }
@@ -64,23 +92,43 @@ impl<'a> Display for _Field<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let indent = " ".repeat(4 * 3);
         if let Some(description) = self.description {
-            write!(
+            writeln!(
                 f,
-                "{indent}#[doc = \"{description}\"]\n",
+                "{indent}#[doc = \"{description}\"]",
                 description = description,
                 indent = indent
             )?;
         }
         write_access!(f, self.access, indent);
+        write_modified_write_values!(f, self.modified_write_values, indent);
+        let dim = match self.dim {
+            Some((count, step)) => format!("; {} step {}", count, step),
+            None => String::new(),
+        };
         write!(
             f,
-            "{indent}{name}[{offset}:{width}] as {ty}",
+            "{indent}{name}[{offset}:{width}{dim}] as {ty}",
             name = self.name,
             offset = self.offset,
             width = self.width,
+            dim = dim,
             ty = self.ty,
             indent = indent
-        )
+        )?;
+        if let Some(scaling) = self.scaling {
+            let mut keys = Vec::new();
+            if let Some(scale) = scaling.scale {
+                keys.push(format!("scale: {:?}", scale));
+            }
+            if let Some(offset) = scaling.offset {
+                keys.push(format!("offset: {:?}", offset));
+            }
+            if let Some(signed) = scaling.signed {
+                keys.push(format!("signed: {}", signed));
+            }
+            write!(f, " {{ {} }}", keys.join(", "))?;
+        }
+        Ok(())
     }
 }
 
@@ -93,6 +141,11 @@ trait DerivedField<'a> {
 
     fn access(&self, fields: &HashMap<String, &'a Field>) -> Option<Access>;
 
+    fn modified_write_values(
+        &self,
+        fields: &HashMap<String, &'a Field>,
+    ) -> Option<ModifiedWriteValues>;
+
     fn overrides(
         &'a self,
         overrides: Option<&'a HashMap<String, FieldOverrides>>,
@@ -118,6 +171,11 @@ impl<'a> DerivedField<'a> for Field {
         overrides
             .and_then(|overrides| overrides.ty.as_ref())
             .map(|ty_name| ty_name.as_str())
+            .or_else(|| {
+                overrides
+                    .filter(|overrides| overrides.is_scaled())
+                    .map(|_| "f32")
+            })
             .unwrap_or_else(|| {
                 let width = self.bit_range.width;
                 match width {
@@ -140,6 +198,18 @@ impl<'a> DerivedField<'a> for Field {
         self.access.or(derived)
     }
 
+    fn modified_write_values(
+        &self,
+        fields: &HashMap<String, &'a Field>,
+    ) -> Option<ModifiedWriteValues> {
+        let derived = self
+            .derived_from
+            .as_ref()
+            .and_then(|name| fields.get(name))
+            .and_then(|f| f.modified_write_values(fields));
+        self.modified_write_values.or(derived)
+    }
+
     fn overrides(
         &'a self,
         overrides: Option<&'a HashMap<String, FieldOverrides>>,
@@ -35,7 +35,7 @@ fn generate_peripherals(src_dir: &Path, peripherals: _Peripherals) -> Result<()>
 }
 
 fn generate_peripheral(peripherals_dir: &Path, peripheral: _Peripheral) -> Result<()> {
-    let peripheral_dir = peripherals_dir.join(peripheral.name.to_string());
+    let peripheral_dir = peripherals_dir.join(&peripheral.name);
     create_dir(&peripheral_dir)?;
 
     write(peripheral_dir.join("mod.rs"), peripheral.to_string())?;
@@ -49,7 +49,7 @@ fn generate_peripheral(peripherals_dir: &Path, peripheral: _Peripheral) -> Resul
 }
 
 fn generate_cluster(parent_dir: &Path, cluster: _Cluster) -> Result<()> {
-    let cluster_dir = parent_dir.join(cluster.name.to_string());
+    let cluster_dir = parent_dir.join(&cluster.name);
     create_dir(&cluster_dir)?;
 
     write(cluster_dir.join("mod.rs"), cluster.to_string())?;
@@ -63,7 +63,7 @@ fn generate_cluster(parent_dir: &Path, cluster: _Cluster) -> Result<()> {
 }
 
 fn generate_register(parent_dir: &Path, register: _Register) -> Result<()> {
-    let register_file = parent_dir.join(format!("{}.rs", register.name.to_string()));
+    let register_file = parent_dir.join(format!("{}.rs", register.name));
     write(register_file, register.to_string())
 }
 
@@ -22,6 +22,11 @@
 //! ```yaml
 //! name: <alternate device name> // Must be identifier
 //! description: <alternate device description>
+//! access_width: 32 // Bus access width, wider registers are accessed in several parts
+//! access_order: low-then-high // Order of partial accesses, `low-then-high` or `high-then-low`
+//! byte_order: big-endian // Byte order of registers, `native`, `little-endian` or `big-endian`, `big-endian` if so is SVD cpu endian
+//! reserved_bits: preserve // How bits not covered by writable fields are written, `preserve`, `zero` or `reset-value`
+//! interface: bus // Registers interface, `memory-mapped` or `bus` for external devices accessed via `ral::Transport`
 //! peripherals:
 //!   <peripheral name>: // as can be found in .svd file
 //!     name: <alternate peripheral name> // Must be identifier
@@ -29,6 +34,17 @@
 //!     features: // List of features for conditional compilation, e.g. packaging names
 //!       - <feature1> // to exclude peripheral if it's not available
 //!       - <feature2>
+//!     byte_order: big-endian // Overrides byte order for peripheral registers
+//!     reserved_bits: zero // Overrides reserved bits policy for peripheral registers
+//!     interface: bus // Overrides registers interface for the peripheral
+//!     clock: rcc.apb1enr.tim2en // Field enabling peripheral clock, as `<peripheral>.[<cluster>.]<register>.<field>` path of generated modules,
+//!                               // peripheral registers are then obtained only via `Clock` token returned by `enable_clock()`,
+//!                               // field must be a single bit of non-array register in a peripheral not gated itself
+//!     state: // Registers covered by peripheral `save()` and `restore()`, as `[<cluster>.]<register>` paths of generated modules
+//!       exclude: // Registers not to be saved and restored
+//!         - cnt
+//!       restore_order: // Registers to be restored first in specified order, the rest are restored in SVD order
+//!         - dma.dcr
 //!     clusters:
 //!       <cluster name>: // as can be found in .svd file
 //!         name: <alternate cluster name> // Must be identifier
@@ -45,17 +61,46 @@
 //!             - <feature2>
 //!           uses: // use expressions required for field type overrides
 //!             - crate_name::types::CustomType
+//!           access_width: 32 // Overrides bus access width for the register
+//!           access_order: high-then-low // Overrides order of partial accesses for the register
+//!           byte_order: little-endian // Overrides byte order for the register
+//!           reserved_bits: reset-value // Overrides reserved bits policy for the register
+//!           read_action: clear // SVD `readAction` of the register, `clear` excludes it from `reset_all()`
 //!           fields:
 //!             <field name>: // as can be found in .svd file
 //!               name: <alternate field name> // Must be identifier
 //!               description: <alternate field description>
-//!               type: CustomType // Required use expression must be specified in register's uses section
+//!               type: CustomType // Required use expression must be specified in register's uses section,
+//!                                // fixed-point types, e.g. `Fixed<i16, 8>`, and signed types, e.g. `i8`, need no uses
+//!               scale: 0.5 // Linear scale of raw field value, field is generated as `f32` unless type is `f64`
+//!               offset: -40 // Offset added to scaled raw field value, `value = raw * scale + offset`
+//!               signed: true // Whether raw value of scaled field is two's complement
 //! ```
 //! All fields are optional
 //!
+//! # External devices
+//!
+//! Crate for external chip (e.g. PMIC or sensor connected via I2C or SPI) can be generated from SVD
+//! describing its register map, setting `interface: bus` on device level of overrides,
+//! so generated registers are accessed through user supplied `ral::Transport`
+//!
 //! # What will be generated
 //!
-//! Module structure described in [ral](https://docs.rs/ral) crate documentation will be generated
+//! Module structure described in [ral](https://docs.rs/ral) crate documentation will be generated,
+//! including `METADATA` tables on device, peripheral and cluster levels
+//! and `reset_all()` functions writing reset values to writable registers in SVD order.
+//! Read-only, clear-on-read and bus-backed registers are skipped, device level function skips
+//! clock gated peripherals, so no clock is enabled as a side effect, those are reset with
+//! peripheral level `reset_all(&clock)`. Resetting stops at the first register borrowed elsewhere,
+//! leaving registers written before it reset.
+//! Each peripheral also gets `PeripheralState` type with `save()` and `restore(&state)` functions
+//! covering its readable and writable registers, e.g. to preserve peripheral configuration across low-power modes.
+//! Restoring leaves fields with side effect on write (e.g. `oneToClear` flags) untouched.
+//! SVD register arrays named with `%s` placeholder and without `dimIndex` are generated as
+//! [register arrays](https://docs.rs/ral/latest/ral/#register-arrays) borrowed by index, e.g. `ccr(2)`,
+//! other arrays as well as bus-backed ones are flattened to separate registers.
+//! SVD field arrays are generated the same way as [field arrays](https://docs.rs/ral/latest/ral/#field-arrays)
+//! with indexed accessors, e.g. `set_moder(3, value)`
 //!
 //! Also `Cargo.toml` will be generated with content
 //! ```toml
@@ -103,7 +148,7 @@ pub fn generate(
     let project_dir = Path::new(project_dir);
     let device = load_device(svd_file_name)?;
     let overrides = load_overrides(overrides_file_name)?;
-    generate::generate_package(project_dir, _Package::build(&device, overrides.as_ref()))?;
+    generate::generate_package(project_dir, _Package::build(&device, overrides.as_ref())?)?;
     Ok(())
 }
 
@@ -121,5 +166,6 @@ fn load_overrides(overrides_file_name: Option<&str>) -> Result<Option<DeviceOver
     File::open(overrides_file_name.unwrap())?.read_to_string(file)?;
 
     let overrides: DeviceOverrides = serde_yaml::from_str(file)?;
+    overrides.validate()?;
     Ok(Some(overrides))
 }
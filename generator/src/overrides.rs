@@ -5,18 +5,59 @@ use std::collections::HashMap;
 pub(super) struct DeviceOverrides {
     pub(super) name: Option<String>,
     pub(super) description: Option<String>,
+    pub(super) access_width: Option<u32>,
+    pub(super) access_order: Option<String>,
+    pub(super) byte_order: Option<String>,
+    pub(super) reserved_bits: Option<String>,
+    pub(super) interface: Option<String>,
     pub(super) peripherals: Option<HashMap<String, PeripheralOverrides>>,
 }
 
+impl DeviceOverrides {
+    /// Checks combinations of overrides which can't be generated
+    pub(super) fn validate(&self) -> anyhow::Result<()> {
+        for (peripheral_name, peripheral) in self.peripherals.iter().flatten() {
+            for (register_name, register) in peripheral.registers.iter().flatten() {
+                for (field_name, field) in register.fields.iter().flatten() {
+                    match &field.ty {
+                        Some(ty) if field.is_scaled() && ty != "f32" && ty != "f64" => {
+                            anyhow::bail!(
+                                "Field {}.{}.{} cannot be scaled as {}, 'scale', 'offset' and 'signed' require f32 or f64 type",
+                                peripheral_name,
+                                register_name,
+                                field_name,
+                                ty
+                            )
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Deserialize)]
 pub(super) struct PeripheralOverrides {
     pub(super) name: Option<String>,
     pub(super) description: Option<String>,
     pub(super) features: Option<Vec<String>>,
+    pub(super) byte_order: Option<String>,
+    pub(super) reserved_bits: Option<String>,
+    pub(super) interface: Option<String>,
+    pub(super) clock: Option<String>,
+    pub(super) state: Option<StateOverrides>,
     pub(super) clusters: Option<HashMap<String, ClusterOverrides>>,
     pub(super) registers: Option<HashMap<String, RegisterOverrides>>,
 }
 
+#[derive(Deserialize)]
+pub(super) struct StateOverrides {
+    pub(super) exclude: Option<Vec<String>>,
+    pub(super) restore_order: Option<Vec<String>>,
+}
+
 #[derive(Deserialize)]
 pub(super) struct ClusterOverrides {
     pub(super) name: Option<String>,
@@ -30,6 +71,11 @@ pub(super) struct RegisterOverrides {
     pub(super) description: Option<String>,
     pub(super) features: Option<Vec<String>>,
     pub(super) uses: Option<Vec<String>>,
+    pub(super) access_width: Option<u32>,
+    pub(super) access_order: Option<String>,
+    pub(super) byte_order: Option<String>,
+    pub(super) reserved_bits: Option<String>,
+    pub(super) read_action: Option<String>,
     pub(super) fields: Option<HashMap<String, FieldOverrides>>,
 }
 
@@ -39,4 +85,14 @@ pub(super) struct FieldOverrides {
     pub(super) description: Option<String>,
     #[serde(rename(deserialize = "type"))]
     pub(super) ty: Option<String>,
+    pub(super) scale: Option<f64>,
+    pub(super) offset: Option<f64>,
+    pub(super) signed: Option<bool>,
+}
+
+impl FieldOverrides {
+    /// Whether field is linearly scaled, it's generated as `f32` field unless type is overridden as well
+    pub(super) fn is_scaled(&self) -> bool {
+        self.scale.is_some() || self.offset.is_some() || self.signed.is_some()
+    }
 }
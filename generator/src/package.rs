@@ -13,11 +13,11 @@ impl<'a> _Package<'a> {
     pub(super) fn build(
         device: &'a Device,
         overrides: Option<&'a DeviceOverrides>,
-    ) -> _Package<'a> {
-        _Package {
-            device: _Device::build(device, overrides),
+    ) -> anyhow::Result<_Package<'a>> {
+        Ok(_Package {
+            device: _Device::build(device, overrides)?,
             features: Self::collect_features(overrides),
-        }
+        })
     }
 
     fn collect_features(overrides: Option<&'a DeviceOverrides>) -> Option<Vec<&'a String>> {
@@ -28,7 +28,7 @@ impl<'a> _Package<'a> {
                     .values()
                     .flat_map(|peripheral| {
                         let mut collected = Vec::<&'a String>::new();
-                        peripheral.features.iter().flat_map(|vec| vec).fold(
+                        peripheral.features.iter().flatten().fold(
                             &mut collected,
                             |collected, value| {
                                 collected.push(value);
@@ -40,7 +40,7 @@ impl<'a> _Package<'a> {
                             .iter()
                             .flat_map(|map| map.values())
                             .flat_map(|overrides| overrides.features.iter())
-                            .flat_map(|vec| vec)
+                            .flatten()
                             .fold(&mut collected, |collected, value| {
                                 collected.push(value);
                                 collected
@@ -50,7 +50,7 @@ impl<'a> _Package<'a> {
                             .iter()
                             .flat_map(|map| map.values())
                             .flat_map(|overrides| overrides.features.iter())
-                            .flat_map(|vec| vec)
+                            .flatten()
                             .fold(&mut collected, |collected, value| {
                                 collected.push(value);
                                 collected
@@ -82,9 +82,9 @@ impl<'a> Display for _Package<'a> {
             name = &self.device.name
         )?;
         if let Some(description) = self.device.description {
-            write!(
+            writeln!(
                 f,
-                "description = \"{description}\"\n",
+                "description = \"{description}\"",
                 description = description
             )?;
         }
@@ -102,7 +102,7 @@ impl<'a> Display for _Package<'a> {
         if let Some(features) = self.features.as_ref() {
             write!(f, "\n[features]\n")?;
             for feature in features {
-                write!(f, "{feature} = []\n", feature = feature)?;
+                writeln!(f, "{feature} = []", feature = feature)?;
             }
         }
         Ok(())
@@ -1,20 +1,134 @@
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter, Result};
 
-use svd_parser::{Cluster, Device, Peripheral, Register, RegisterCluster, RegisterProperties};
+use convert_case::{Case, Casing};
+use indoc::formatdoc;
+
+use svd_parser::{
+    Cluster, Device, Endian, Peripheral, Register, RegisterCluster, RegisterProperties,
+};
 
 use crate::cluster::_Cluster;
-use crate::overrides::{DeviceOverrides, PeripheralOverrides};
+use crate::overrides::{DeviceOverrides, PeripheralOverrides, StateOverrides};
 use crate::register::_Register;
-use crate::utils::{array_names, build_children, build_ident, merge_defaults};
+use crate::utils::{
+    array_names, build_children, build_ident, indent, merge_defaults, BusProperties, Child,
+};
 
 pub(super) struct _Peripheral<'a> {
     pub(super) name: String,
     description: Option<&'a String>,
-    features: Option<&'a Vec<String>>,
+    pub(super) features: Option<&'a Vec<String>>,
     base_address: u64,
+    pub(super) clock: Option<ClockField>,
     pub(super) clusters: Vec<_Cluster<'a>>,
     pub(super) registers: Vec<_Register<'a>>,
+    order: Vec<Child>,
+    state: Vec<StateRegister>,
+}
+
+/// Field enabling peripheral clock
+pub(super) struct ClockField {
+    /// `<peripheral>.[<cluster>.]<register>.<field>` path as specified in overrides
+    path: String,
+    /// Path of the module exporting register borrow functions, e.g. `rcc::ahb1`
+    module: String,
+    register: String,
+    field: String,
+}
+
+impl ClockField {
+    fn parse(clock: &str) -> anyhow::Result<ClockField> {
+        let path: Vec<&str> = clock.split('.').collect();
+        match path.as_slice() {
+            [modules @ .., register, field]
+                if !modules.is_empty() && path.iter().all(|name| is_identifier(name)) =>
+            {
+                Ok(ClockField {
+                    path: String::from(clock),
+                    module: modules.join("::"),
+                    register: String::from(*register),
+                    field: String::from(*field),
+                })
+            }
+            _ => Err(anyhow::anyhow!(
+                "Clock must be specified as <peripheral>.[<cluster>.]<register>.<field>, found {}",
+                clock
+            )),
+        }
+    }
+
+    /// Checks that clock field exists, is a single bit of non-array register and its peripheral isn't gated
+    fn resolve(&self, peripheral: &str, peripherals: &[_Peripheral]) -> anyhow::Result<()> {
+        let error = |reason: String| {
+            anyhow::anyhow!(
+                "Clock {} of peripheral {} is invalid: {}",
+                self.path,
+                peripheral,
+                reason
+            )
+        };
+        let path: Vec<&str> = self.module.split("::").collect();
+        let owner = peripherals
+            .iter()
+            .find(|owner| owner.name == path[0])
+            .ok_or_else(|| error(format!("peripheral {} not found", path[0])))?;
+        if owner.clock.is_some() {
+            Err(error(format!(
+                "peripheral {} is itself clock gated",
+                owner.name
+            )))?
+        }
+        let mut registers = &owner.registers;
+        let mut clusters = &owner.clusters;
+        for name in &path[1..] {
+            let cluster = clusters
+                .iter()
+                .find(|cluster| &cluster.name == name)
+                .ok_or_else(|| error(format!("cluster {} not found", name)))?;
+            registers = &cluster.registers;
+            clusters = &cluster.clusters;
+        }
+        let register = registers
+            .iter()
+            .find(|register| register.name == self.register)
+            .ok_or_else(|| error(format!("register {} not found", self.register)))?;
+        if register.is_array() {
+            Err(error(format!("register {} is an array", self.register)))?
+        }
+        let field = register
+            .field(&self.field)
+            .ok_or_else(|| error(format!("field {} not found", self.field)))?;
+        if !field.is_flag() {
+            Err(error(format!("field {} is not a single bit", self.field)))?
+        }
+        Ok(())
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first == '_' || first.is_ascii_alphabetic())
+        .unwrap_or(false)
+        && chars.all(|char| char == '_' || char.is_ascii_alphanumeric())
+}
+
+/// Register covered by peripheral state
+struct StateRegister {
+    /// `[<cluster>.]<register>` path of generated modules, used in overrides
+    path: String,
+    /// Path of the module exporting register borrow functions, e.g. `dma::`
+    module: String,
+    /// Prefix of clock token methods and state fields, e.g. `dma_`
+    prefix: String,
+    name: String,
+    value_size: u32,
+    /// Arguments of borrow function, indices for register array
+    indices: Vec<String>,
+    array: bool,
+    cfgs: Vec<String>,
 }
 
 impl<'a> _Peripheral<'a> {
@@ -22,37 +136,158 @@ impl<'a> _Peripheral<'a> {
         peripheral: &'a Peripheral,
         peripherals: &HashMap<String, &'a Peripheral>,
         defaults: RegisterProperties,
+        bus: BusProperties<'a>,
         overrides: Option<&'a HashMap<String, PeripheralOverrides>>,
-    ) -> _Peripheral<'a> {
+    ) -> anyhow::Result<_Peripheral<'a>> {
         let overrides = peripheral.overrides(overrides);
         let children = peripheral
             .registers(peripherals)
             .expect("There should be no empty peripherals");
         let mut clusters = HashMap::<String, &'a Cluster>::new();
         let mut registers = HashMap::<String, &'a Register>::new();
-        Self::collect_clusters_and_registers(&children, &mut clusters, &mut registers);
+        Self::collect_clusters_and_registers(children, &mut clusters, &mut registers);
 
         let defaults = merge_defaults(
             peripheral.default_register_properties(peripherals),
             defaults,
         );
+        let bus = BusProperties {
+            byte_order: overrides
+                .and_then(|overrides| overrides.byte_order.as_deref())
+                .or(bus.byte_order),
+            reserved_bits: overrides
+                .and_then(|overrides| overrides.reserved_bits.as_ref())
+                .or(bus.reserved_bits),
+            interface: overrides
+                .and_then(|overrides| overrides.interface.as_ref())
+                .or(bus.interface),
+            ..bus
+        };
         let cluster_overrides = overrides.and_then(|overrides| overrides.clusters.as_ref());
         let register_overrides = overrides.and_then(|overrides| overrides.registers.as_ref());
-        let (clusters, registers) = build_children(
+        let (mut clusters, registers, order) = build_children(
             children,
             &clusters,
             &registers,
             defaults,
+            bus,
             cluster_overrides,
             register_overrides,
         );
-        _Peripheral {
+        let state = Self::build_state(
+            &clusters,
+            &registers,
+            &order,
+            overrides.and_then(|overrides| overrides.state.as_ref()),
+        )?;
+        let clock = overrides
+            .and_then(|overrides| overrides.clock.as_deref())
+            .map(ClockField::parse)
+            .transpose()?;
+        if clock.is_some() {
+            for cluster in &mut clusters {
+                cluster.gate(String::from("super::Clock"), "");
+            }
+        }
+        Ok(_Peripheral {
             name: peripheral.name(overrides),
             description: peripheral.description(overrides),
             features: overrides.and_then(|overrides| overrides.features.as_ref()),
             base_address: peripheral.base_address,
+            clock,
             clusters,
             registers,
+            order,
+            state,
+        })
+    }
+
+    /// Collects saveable registers in SVD order, then applies exclusions and restore order overrides
+    fn build_state(
+        clusters: &[_Cluster<'a>],
+        registers: &[_Register<'a>],
+        order: &[Child],
+        overrides: Option<&StateOverrides>,
+    ) -> anyhow::Result<Vec<StateRegister>> {
+        let mut state = Vec::new();
+        Self::collect_state(clusters, registers, order, "", "", "", &[], &mut state);
+        let take = |state: &mut Vec<StateRegister>, path: &String| {
+            state
+                .iter()
+                .position(|register| &register.path == path)
+                .map(|index| state.remove(index))
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "State register {} must be readable and writable register of the peripheral",
+                        path
+                    )
+                })
+        };
+        if let Some(exclude) = overrides.and_then(|overrides| overrides.exclude.as_ref()) {
+            for path in exclude {
+                take(&mut state, path)?;
+            }
+        }
+        match overrides.and_then(|overrides| overrides.restore_order.as_ref()) {
+            Some(restore_order) => {
+                let mut ordered = Vec::new();
+                for path in restore_order {
+                    ordered.push(take(&mut state, path)?);
+                }
+                ordered.extend(state);
+                Ok(ordered)
+            }
+            None => Ok(state),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn collect_state(
+        clusters: &[_Cluster<'a>],
+        registers: &[_Register<'a>],
+        order: &[Child],
+        path: &str,
+        module: &str,
+        prefix: &str,
+        cfgs: &[String],
+        state: &mut Vec<StateRegister>,
+    ) {
+        for child in order {
+            match *child {
+                Child::Cluster(index) => {
+                    let cluster = &clusters[index];
+                    let mut cfgs = cfgs.to_vec();
+                    cfgs.extend(features_cfg!(cluster));
+                    Self::collect_state(
+                        &cluster.clusters,
+                        &cluster.registers,
+                        &cluster.order,
+                        &format!("{}{}.", path, cluster.name),
+                        &format!("{}{}::", module, cluster.name),
+                        &format!("{}{}_", prefix, cluster.name),
+                        &cfgs,
+                        state,
+                    );
+                }
+                Child::Register(index) => {
+                    let register = &registers[index];
+                    if !register.is_saveable() {
+                        continue;
+                    }
+                    let mut cfgs = cfgs.to_vec();
+                    cfgs.extend(features_cfg!(register));
+                    state.push(StateRegister {
+                        path: format!("{}{}", path, register.name),
+                        module: String::from(module),
+                        prefix: String::from(prefix),
+                        name: register.name.clone(),
+                        value_size: register.value_size,
+                        indices: register.indices(),
+                        array: register.is_array(),
+                        cfgs,
+                    });
+                }
+            }
         }
     }
 
@@ -102,21 +337,198 @@ impl<'a> Display for _Peripheral<'a> {
         }
         write!(
             f,
-            "const BASE_ADDRESS: usize = {base_address:#X};\n\n",
+            "pub const BASE_ADDRESS: usize = {base_address:#X};\n\n",
             base_address = self.base_address
         )?;
-        write_children!(self, f);
+        match &self.clock {
+            Some(clock) => {
+                write_clock(f, clock)?;
+                write_gated_children!(self, f, "Clock", "");
+            }
+            None => {
+                write_children!(self, f);
+            }
+        }
+        write_reset_all!(self, f, self.clock.as_ref().map(|_| "Clock"));
+        write_state(f, &self.state, self.clock.is_some())?;
+        write!(
+            f,
+            "\npub const METADATA: ral::PeripheralMetadata = ral::PeripheralMetadata {{\n    name: \"{name}\",\n",
+            name = self.name
+        )?;
+        write_optional_str!(f, "description", self.description);
+        writeln!(f, "    address: BASE_ADDRESS,")?;
+        write_children_metadata!(self, f);
+        writeln!(f, "}};")?;
         Ok(())
     }
 }
 
+/// Writes peripheral state type with `save()` and `restore()` functions
+fn write_state(f: &mut Formatter<'_>, state: &[StateRegister], gated: bool) -> Result {
+    let write_cfgs = |f: &mut Formatter<'_>, register: &StateRegister, level: usize| {
+        for cfg in &register.cfgs {
+            write!(f, "{}", indent(cfg.clone(), level))?;
+        }
+        Ok(())
+    };
+    let borrow = |register: &StateRegister, index: &str| {
+        if gated {
+            format!("clock.try_{}{}({})", register.prefix, register.name, index)
+        } else {
+            format!("{}try_{}({})", register.module, register.name, index)
+        }
+    };
+    let unused = if state.is_empty() { "_" } else { "" };
+    let clock = if gated {
+        format!("{}clock: &Clock", unused)
+    } else {
+        String::new()
+    };
+    writeln!(
+        f,
+        "\n/// Saved values of readable and writable peripheral registers, e.g. to be restored after low-power mode\n#[derive(Clone, Copy)]\npub struct PeripheralState {{"
+    )?;
+    for register in state {
+        write_cfgs(f, register, 1)?;
+        let value_type = if register.array {
+            format!("[u{}; {}]", register.value_size, register.indices.len())
+        } else {
+            format!("u{}", register.value_size)
+        };
+        writeln!(
+            f,
+            "    {prefix}{name}: {value_type},",
+            prefix = register.prefix,
+            name = register.name,
+            value_type = value_type
+        )?;
+    }
+    writeln!(
+        f,
+        "}}\n\n/// Saves values of readable and writable registers, fails if any of the registers is borrowed elsewhere\npub fn save({clock}) -> Result<PeripheralState, ral::BorrowError> {{",
+        clock = clock
+    )?;
+    if !state.is_empty() {
+        writeln!(f, "    use ral::ReadableRegister;")?;
+    }
+    writeln!(f, "    Ok(PeripheralState {{")?;
+    for register in state {
+        write_cfgs(f, register, 2)?;
+        let values: Vec<String> = register
+            .indices
+            .iter()
+            .map(|index| format!("{}?.read().get_bits()", borrow(register, index)))
+            .collect();
+        let value = if register.array {
+            format!("[{}]", values.join(", "))
+        } else {
+            values.join("")
+        };
+        writeln!(
+            f,
+            "        {prefix}{name}: {value},",
+            prefix = register.prefix,
+            name = register.name,
+            value = value
+        )?;
+    }
+    writeln!(
+        f,
+        "    }})\n}}\n\n/// Restores values of registers saved with `save()`, fails if any of the registers is borrowed elsewhere\npub fn restore({clock}{separator}{unused}state: &PeripheralState) -> Result<(), ral::BorrowError> {{",
+        clock = clock,
+        separator = if gated { ", " } else { "" },
+        unused = unused
+    )?;
+    if !state.is_empty() {
+        writeln!(f, "    use ral::WritableRegister;")?;
+    }
+    for register in state {
+        for index in &register.indices {
+            write_cfgs(f, register, 1)?;
+            let element = if register.array {
+                format!("[{}]", index)
+            } else {
+                String::new()
+            };
+            writeln!(
+                f,
+                "    {borrow}?.restore_bits(state.{prefix}{name}{element}).write();",
+                borrow = borrow(register, index),
+                prefix = register.prefix,
+                name = register.name,
+                element = element
+            )?;
+        }
+    }
+    writeln!(f, "    Ok(())\n}}")
+}
+
+/// Writes clock token type and function enabling the clock with the field
+fn write_clock(f: &mut Formatter<'_>, clock: &ClockField) -> Result {
+    f.write_str(&formatdoc!(
+        "
+        use ral::{{ReadableRegister, WritableRegister}};
+
+        /// Token proving that peripheral clock is enabled, peripheral registers are obtained via it
+        pub struct Clock(());
+
+        /// Enables peripheral clock by setting `{clock}` field,
+        /// returns `None` if clock enable register is borrowed elsewhere
+        #[track_caller]
+        pub fn enable_clock() -> Option<Clock> {{
+            try_enable_clock().ok()
+        }}
+
+        /// Enables peripheral clock by setting `{clock}` field,
+        /// reports who holds clock enable register if it's borrowed elsewhere
+        #[track_caller]
+        pub fn try_enable_clock() -> Result<Clock, ral::BorrowError> {{
+            super::{module}::try_{register}()?.read().set_{field}().write();
+            Ok(Clock(()))
+        }}
+
+        impl Clock {{
+            /// Assumes peripheral clock is already enabled, e.g. by bootloader
+            ///
+            /// # Safety
+            /// Accessing peripheral registers with disabled clock may result in hard fault
+            pub unsafe fn assume_enabled() -> Clock {{
+                Clock(())
+            }}
+        }}
+
+        ",
+        clock = clock.path,
+        module = clock.module,
+        register = clock.register,
+        field = clock.field
+    ))
+}
+
 pub(super) struct _Peripherals<'a>(Vec<_Peripheral<'a>>);
 
 impl<'a> _Peripherals<'a> {
     pub(super) fn build(
         device: &'a Device,
         overrides: Option<&'a DeviceOverrides>,
-    ) -> _Peripherals<'a> {
+    ) -> anyhow::Result<_Peripherals<'a>> {
+        let bus = BusProperties {
+            width: overrides
+                .and_then(|overrides| overrides.access_width)
+                .or(device.width),
+            access_order: overrides.and_then(|overrides| overrides.access_order.as_ref()),
+            byte_order: overrides
+                .and_then(|overrides| overrides.byte_order.as_deref())
+                .or_else(|| {
+                    device.cpu.as_ref().and_then(|cpu| match cpu.endian {
+                        Endian::Big => Some("big-endian"),
+                        _ => None,
+                    })
+                }),
+            reserved_bits: overrides.and_then(|overrides| overrides.reserved_bits.as_ref()),
+            interface: overrides.and_then(|overrides| overrides.interface.as_ref()),
+        };
         let overrides = overrides.and_then(|overrides| overrides.peripherals.as_ref());
         let mut peripherals = HashMap::<String, &'a Peripheral>::new();
         for peripheral in &device.peripherals {
@@ -126,13 +538,19 @@ impl<'a> _Peripherals<'a> {
         let mut collected = Vec::new();
         for peripheral in &device.peripherals {
             collected.push(_Peripheral::build(
-                &peripheral,
+                peripheral,
                 &peripherals,
                 defaults,
+                bus,
                 overrides,
-            ));
+            )?);
+        }
+        for peripheral in &collected {
+            if let Some(clock) = &peripheral.clock {
+                clock.resolve(&peripheral.name, &collected)?;
+            }
         }
-        _Peripherals(collected)
+        Ok(_Peripherals(collected))
     }
 }
 
@@ -142,12 +560,18 @@ impl<'a> Display for _Peripherals<'a> {
             if let Some(packages_cfg) = features_cfg!(peripheral) {
                 write!(f, "{}", packages_cfg)?;
             }
-            write!(f, "pub mod {};\n", peripheral.name)?;
+            writeln!(f, "pub mod {};", peripheral.name)?;
         }
         Ok(())
     }
 }
 
+impl<'a> _Peripherals<'a> {
+    pub(super) fn iter(&self) -> std::slice::Iter<'_, _Peripheral<'a>> {
+        self.0.iter()
+    }
+}
+
 impl<'a> IntoIterator for _Peripherals<'a> {
     type Item = <Vec<_Peripheral<'a>> as IntoIterator>::Item;
     type IntoIter = <Vec<_Peripheral<'a>> as IntoIterator>::IntoIter;
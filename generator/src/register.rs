@@ -2,11 +2,11 @@ use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
 use indoc::{formatdoc, writedoc};
-use svd_parser::{Access, Field, Register, RegisterProperties};
+use svd_parser::{Access, Field, ModifiedWriteValues, Register, RegisterProperties};
 
 use crate::field::_Field;
 use crate::overrides::RegisterOverrides;
-use crate::utils::{array_names, build_ident, indent};
+use crate::utils::{array_names, build_ident, indent, BusProperties};
 
 #[derive(Clone)]
 pub(super) struct _Register<'a> {
@@ -16,7 +16,15 @@ pub(super) struct _Register<'a> {
     pub(super) features: Option<&'a Vec<String>>,
     pub(super) uses: Option<&'a Vec<String>>,
     offset: u64,
-    value_size: u32,
+    pub(super) value_size: u32,
+    access_width: Option<u32>,
+    access_order: Option<&'a String>,
+    byte_order: Option<&'a str>,
+    reserved_bits: Option<&'a String>,
+    interface: Option<&'a String>,
+    read_action: Option<&'a String>,
+    /// Count and stride of register array
+    dim: Option<(u32, u32)>,
     reset_mask: u64,
     reset_value: u64,
     fields: Option<Vec<_Field<'a>>>,
@@ -27,11 +35,23 @@ impl<'a> _Register<'a> {
         register: &'a Register,
         registers: &HashMap<String, &'a Register>,
         defaults: RegisterProperties,
+        bus: BusProperties<'a>,
         overrides: Option<&'a HashMap<String, RegisterOverrides>>,
     ) -> Vec<_Register<'a>> {
-        let _register = _Register::build(register, registers, defaults, overrides);
+        let _register = _Register::build(register, registers, defaults, bus, overrides);
         match register {
             Register::Single(_) => vec![_register],
+            Register::Array(_, dim)
+                if dim.dim_index.is_none()
+                    && _register.name.contains("%s")
+                    && !_register.is_bus() =>
+            {
+                vec![_Register {
+                    name: build_ident(&_register.name.replace("[%s]", "").replace("%s", "")),
+                    dim: Some((dim.dim, dim.dim_increment)),
+                    .._register
+                }]
+            }
             Register::Array(_, dim) => {
                 let mut registers = Vec::new();
                 let mut offset = _register.offset;
@@ -42,7 +62,7 @@ impl<'a> _Register<'a> {
                         fields: _register.fields.clone(),
                         .._register
                     });
-                    offset = offset + dim.dim_increment as u64;
+                    offset += dim.dim_increment as u64;
                 }
                 registers
             }
@@ -53,41 +73,116 @@ impl<'a> _Register<'a> {
         register: &'a Register,
         registers: &HashMap<String, &'a Register>,
         defaults: RegisterProperties,
+        bus: BusProperties<'a>,
         overrides: Option<&'a HashMap<String, RegisterOverrides>>,
     ) -> _Register<'a> {
         let overrides = register.overrides(overrides);
         let access = register.access(registers).or(defaults.access);
+        let modified_write_values = register.modified_write_values(registers);
         let fields = register.fields(registers).map(|children| {
             let fields = Self::collect_fields(children);
             let field_overrides = overrides.and_then(|overrides| overrides.fields.as_ref());
             children
                 .iter()
-                .flat_map(|field| _Field::build_all(field, &fields, field_overrides))
+                .flat_map(|field| {
+                    _Field::build_all(field, &fields, modified_write_values, field_overrides)
+                })
                 .collect()
         });
+        let value_size = register
+            .size(registers)
+            .or(defaults.size)
+            .expect("Default size must be specified");
+        // Inherited reset mask may be wider than register, reset value may set bits outside of mask
+        let reset_mask = register
+            .reset_mask(registers)
+            .or(defaults.reset_mask)
+            .expect("Default reset mask must be specified")
+            & (u64::MAX >> (64 - value_size));
+        let name = register.name(overrides);
+        let svd_reset_value = register
+            .reset_value(registers)
+            .or(defaults.reset_value)
+            .expect("Default reset value must be specified");
+        let reset_value = svd_reset_value & reset_mask;
+        if reset_value != svd_reset_value {
+            eprintln!(
+                "warning: reset value {:#X} of register {} sets bits outside of reset mask {:#X}, using {:#X}",
+                svd_reset_value, name, reset_mask, reset_value
+            );
+        }
         _Register {
-            name: register.name(overrides),
+            name,
             description: register.description(overrides),
             access,
             features: overrides.and_then(|overrides| overrides.features.as_ref()),
             uses: overrides.and_then(|overrides| overrides.uses.as_ref()),
             offset: register.address_offset as u64,
-            value_size: register
-                .size(registers)
-                .or(defaults.size)
-                .expect("Default size must be specified"),
-            reset_mask: register
-                .reset_mask(registers)
-                .or(defaults.reset_mask)
-                .expect("Default reset mask must be specified"),
-            reset_value: register
-                .reset_value(registers)
-                .or(defaults.reset_value)
-                .expect("Default reset value must be specified"),
+            value_size,
+            access_width: overrides
+                .and_then(|overrides| overrides.access_width)
+                .or_else(|| bus.width.filter(|&width| width < value_size)),
+            access_order: overrides
+                .and_then(|overrides| overrides.access_order.as_ref())
+                .or(bus.access_order),
+            byte_order: overrides
+                .and_then(|overrides| overrides.byte_order.as_deref())
+                .or(bus.byte_order),
+            reserved_bits: overrides
+                .and_then(|overrides| overrides.reserved_bits.as_ref())
+                .or(bus.reserved_bits),
+            interface: bus.interface,
+            read_action: overrides.and_then(|overrides| overrides.read_action.as_ref()),
+            dim: None,
+            reset_mask,
+            reset_value,
             fields,
         }
     }
 
+    /// Whether register is to be reset by `reset_all()`, read-only, clear-on-read and bus-backed ones aren't
+    pub(super) fn is_resettable(&self) -> bool {
+        let writable = self.access != Some(Access::ReadOnly)
+            && match &self.fields {
+                Some(fields) => fields.is_empty() || fields.iter().any(|field| field.is_writable()),
+                None => true,
+            };
+        let clear_on_read = self.read_action.map(String::as_str) == Some("clear");
+        writable && !clear_on_read && !self.is_bus()
+    }
+
+    /// Whether register is to be covered by peripheral `save()` and `restore()`,
+    /// only resettable registers allowing read without side effects are
+    pub(super) fn is_saveable(&self) -> bool {
+        let readable = matches!(
+            self.access,
+            None | Some(Access::ReadWrite) | Some(Access::ReadWriteOnce)
+        );
+        readable && self.read_action.is_none() && self.is_resettable()
+    }
+
+    fn is_bus(&self) -> bool {
+        self.interface.map(String::as_str) == Some("bus")
+    }
+
+    /// Arguments of borrow function for each register, indices for register array
+    pub(super) fn indices(&self) -> Vec<String> {
+        match self.dim {
+            Some((count, _)) => (0..count).map(|index| index.to_string()).collect(),
+            None => vec![String::new()],
+        }
+    }
+
+    pub(super) fn is_array(&self) -> bool {
+        self.dim.is_some()
+    }
+
+    pub(super) fn field(&self, name: &str) -> Option<&_Field<'a>> {
+        self.fields
+            .as_ref()
+            .and_then(|fields| fields.iter().find(|field| field.name == name))
+    }
+
     fn collect_fields(fields: &Vec<Field>) -> HashMap<String, &Field> {
         let mut map = HashMap::<String, &Field>::new();
         for field in fields {
@@ -132,27 +227,86 @@ impl<'a> Display for _Register<'a> {
             ))?;
         }
         write_access!(f, self.access, " ".repeat(4));
+        if let Some(byte_order) = self.byte_order {
+            f.write_str(&indent(
+                format!(
+                    "#[byte_order = \"{byte_order}\"]\n",
+                    byte_order = byte_order
+                ),
+                1,
+            ))?;
+        }
+        if let Some(reserved_bits) = self.reserved_bits {
+            f.write_str(&indent(
+                format!(
+                    "#[reserved_bits = \"{reserved_bits}\"]\n",
+                    reserved_bits = reserved_bits
+                ),
+                1,
+            ))?;
+        }
+        if let Some(interface) = self.interface {
+            f.write_str(&indent(
+                format!("#[interface = \"{interface}\"]\n", interface = interface),
+                1,
+            ))?;
+        }
         f.write_str(&indent(
             formatdoc!(
                 "
                 {name} {{
                     offset: {offset:#X},
                     value_size: {value_size},
-                    reset_mask: {reset_mask:#X},
-                    reset_value: {reset_value:#X},
                 ",
                 name = build_ident(&self.name),
                 offset = self.offset,
                 value_size = self.value_size,
+            ),
+            1,
+        ))?;
+        if let Some((count, stride)) = self.dim {
+            f.write_str(&indent(
+                format!(
+                    "count: {count},\nstride: {stride:#X},\n",
+                    count = count,
+                    stride = stride
+                ),
+                2,
+            ))?;
+        }
+        if let Some(access_width) = self.access_width {
+            f.write_str(&indent(
+                format!(
+                    "access_width: {access_width},\n",
+                    access_width = access_width
+                ),
+                2,
+            ))?;
+            if let Some(access_order) = self.access_order {
+                f.write_str(&indent(
+                    format!(
+                        "access_order: \"{access_order}\",\n",
+                        access_order = access_order
+                    ),
+                    2,
+                ))?;
+            }
+        }
+        f.write_str(&indent(
+            formatdoc!(
+                "
+                    reset_mask: {reset_mask:#X},
+                    reset_value: {reset_value:#X},
+                ",
                 reset_mask = self.reset_mask,
                 reset_value = self.reset_value,
             ),
-            1,
+            2,
         ))?;
         if let Some(fields) = &self.fields {
             f.write_str(&indent(String::from("fields: {\n"), 2))?;
             for field in fields {
-                write!(f, "{},\n", field)?;
+                writeln!(f, "{},", field)?;
             }
             f.write_str(&indent(String::from("}\n"), 2))?;
         }
@@ -178,6 +332,11 @@ trait DerivedRegister<'a> {
 
     fn access(&self, registers: &HashMap<String, &'a Register>) -> Option<Access>;
 
+    fn modified_write_values(
+        &self,
+        registers: &HashMap<String, &'a Register>,
+    ) -> Option<ModifiedWriteValues>;
+
     fn fields(&'a self, registers: &HashMap<String, &'a Register>) -> Option<&'a Vec<Field>>;
 
     fn overrides(
@@ -237,6 +396,18 @@ impl<'a> DerivedRegister<'a> for Register {
         self.access.or(derived)
     }
 
+    fn modified_write_values(
+        &self,
+        registers: &HashMap<String, &'a Register>,
+    ) -> Option<ModifiedWriteValues> {
+        let derived = self
+            .derived_from
+            .as_ref()
+            .and_then(|name| registers.get(name))
+            .and_then(|&r| r.modified_write_values(registers));
+        self.modified_write_values.or(derived)
+    }
+
     fn fields(&'a self, registers: &HashMap<String, &'a Register>) -> Option<&'a Vec<Field>> {
         let derived = self
             .derived_from
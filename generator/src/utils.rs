@@ -7,7 +7,7 @@ use crate::overrides::{ClusterOverrides, RegisterOverrides};
 use crate::register::_Register;
 use std::ops::Range;
 
-pub(super) fn build_ident(name: &String) -> String {
+pub(super) fn build_ident(name: &str) -> String {
     let name = name.to_lowercase();
     if !accept_as_ident(&name) {
         format!("_{}", name)
@@ -17,8 +17,8 @@ pub(super) fn build_ident(name: &String) -> String {
 }
 
 /// Extracted from [syn](https://crates.io/crates/syn) crate
-fn accept_as_ident(ident: &String) -> bool {
-    match ident.as_str() {
+fn accept_as_ident(ident: &str) -> bool {
+    match ident {
         "_" |
         // Based on https://doc.rust-lang.org/grammar.html#keywords
         // and https://github.com/rust-lang/rfcs/blob/master/text/2421-unreservations-2018.md
@@ -49,7 +49,7 @@ pub(super) fn indent(string: String, indent_level: usize) -> String {
         .join("\n")
 }
 
-pub(super) fn array_names(name: &String, dim: &DimElement) -> Vec<String> {
+pub(super) fn array_names(name: &str, dim: &DimElement) -> Vec<String> {
     dim.dim_index
         .clone()
         .unwrap_or_else(|| {
@@ -69,6 +69,30 @@ pub(super) fn array_names(name: &String, dim: &DimElement) -> Vec<String> {
         .collect()
 }
 
+/// Clock gate of cluster registers: path to peripheral clock token and accessor methods prefix
+#[derive(Clone)]
+pub(super) struct Gate {
+    pub(super) token: String,
+    pub(super) prefix: String,
+}
+
+/// Child of peripheral or cluster, index in its clusters or registers, kept to preserve SVD order
+#[derive(Clone, Copy)]
+pub(super) enum Child {
+    Cluster(usize),
+    Register(usize),
+}
+
+/// Bus related properties inherited by registers
+#[derive(Clone, Copy)]
+pub(super) struct BusProperties<'a> {
+    pub(super) width: Option<u32>,
+    pub(super) access_order: Option<&'a String>,
+    pub(super) byte_order: Option<&'a str>,
+    pub(super) reserved_bits: Option<&'a String>,
+    pub(super) interface: Option<&'a String>,
+}
+
 pub(super) fn merge_defaults(
     current: RegisterProperties,
     parent: RegisterProperties,
@@ -86,34 +110,40 @@ pub(super) fn build_children<'a>(
     clusters: &HashMap<String, &'a Cluster>,
     registers: &HashMap<String, &'a Register>,
     defaults: RegisterProperties,
+    bus: BusProperties<'a>,
     cluster_overrides: Option<&'a HashMap<String, ClusterOverrides>>,
     register_overrides: Option<&'a HashMap<String, RegisterOverrides>>,
-) -> (Vec<_Cluster<'a>>, Vec<_Register<'a>>) {
+) -> (Vec<_Cluster<'a>>, Vec<_Register<'a>>, Vec<Child>) {
     let mut child_clusters = Vec::new();
     let mut child_registers = Vec::new();
+    let mut order = Vec::new();
     for child in children {
         match child {
             RegisterCluster::Cluster(cluster) => {
-                child_clusters.extend(_Cluster::build_all(
+                for cluster in _Cluster::build_all(
                     cluster,
-                    &clusters,
-                    &registers,
+                    clusters,
+                    registers,
                     defaults,
+                    bus,
                     cluster_overrides,
                     register_overrides,
-                ));
+                ) {
+                    order.push(Child::Cluster(child_clusters.len()));
+                    child_clusters.push(cluster);
+                }
             }
             RegisterCluster::Register(register) => {
-                child_registers.extend(_Register::build_all(
-                    register,
-                    &registers,
-                    defaults,
-                    register_overrides,
-                ));
+                for register in
+                    _Register::build_all(register, registers, defaults, bus, register_overrides)
+                {
+                    order.push(Child::Register(child_registers.len()));
+                    child_registers.push(register);
+                }
             }
         }
     }
-    (child_clusters, child_registers)
+    (child_clusters, child_registers, order)
 }
 
 macro_rules! overrides {
@@ -162,6 +192,165 @@ macro_rules! write_children {
         }
     };
 }
+macro_rules! write_gated_children {
+    ($s: ident, $f: ident, $t: expr, $p: expr) => {
+        for cluster in &$s.clusters {
+            if let Some(features_cfg) = features_cfg!(cluster) {
+                write!($f, "{}", features_cfg)?;
+            }
+            writeln!($f, "pub mod {module};", module = &cluster.name)?;
+        }
+        for register in &$s.registers {
+            let features_cfg = features_cfg!(register);
+            if let Some(features_cfg) = &features_cfg {
+                write!($f, "{}", features_cfg)?;
+            }
+            writeln!($f, "mod {module};", module = &register.name)?;
+            if let Some(features_cfg) = &features_cfg {
+                write!($f, "{}", features_cfg)?;
+            }
+            writeln!(
+                $f,
+                "pub use {module}::{register};",
+                module = &register.name,
+                register = register.name.to_case(Case::UpperCamel)
+            )?;
+        }
+        if !$s.registers.is_empty() {
+            writeln!($f, "\nimpl {token} {{", token = $t)?;
+            for register in &$s.registers {
+                let (parameter, argument) = if register.is_array() {
+                    (", index: usize", "index")
+                } else {
+                    ("", "")
+                };
+                let features_cfg = features_cfg!(register).map(|features_cfg| indent(features_cfg, 1));
+                if let Some(features_cfg) = &features_cfg {
+                    write!($f, "{}", features_cfg)?;
+                }
+                writeln!(
+                    $f,
+                    "    /// Borrows `{module}` register\n    #[track_caller]\n    pub fn {prefix}{module}(&self{parameter}) -> Option<{register}> {{\n        {module}::{module}({argument})\n    }}",
+                    prefix = $p,
+                    parameter = parameter,
+                    argument = argument,
+                    module = &register.name,
+                    register = register.name.to_case(Case::UpperCamel)
+                )?;
+                if let Some(features_cfg) = &features_cfg {
+                    write!($f, "{}", features_cfg)?;
+                }
+                writeln!(
+                    $f,
+                    "    /// Borrows `{module}` register, reports who holds it if it's already borrowed\n    #[track_caller]\n    pub fn try_{prefix}{module}(&self{parameter}) -> Result<{register}, ral::BorrowError> {{\n        {module}::try_{module}({argument})\n    }}",
+                    prefix = $p,
+                    parameter = parameter,
+                    argument = argument,
+                    module = &register.name,
+                    register = register.name.to_case(Case::UpperCamel)
+                )?;
+            }
+            writeln!($f, "}}")?;
+        }
+    };
+}
+
+macro_rules! write_reset_all {
+    ($s: ident, $f: ident, $t: expr) => {
+        let resettable = $s.registers.iter().any(|register| register.is_resettable());
+        let clock = match $t {
+            Some(token) if !$s.clusters.is_empty() => format!("clock: &{}", token),
+            Some(token) => format!("_clock: &{}", token),
+            None => String::new(),
+        };
+        writeln!(
+            $f,
+            "\n/// Writes reset values to writable registers in SVD order, skipping read-only, clear-on-read\n/// and bus-backed ones\n///\n/// Fails on the first register borrowed elsewhere, registers written before it stay reset\npub fn reset_all({clock}) -> Result<(), ral::BorrowError> {{",
+            clock = clock
+        )?;
+        if resettable {
+            writeln!($f, "    use ral::WritableRegister;")?;
+        }
+        for child in &$s.order {
+            match *child {
+                Child::Cluster(index) => {
+                    let cluster = &$s.clusters[index];
+                    if let Some(features_cfg) = features_cfg!(cluster) {
+                        write!($f, "{}", indent(features_cfg, 1))?;
+                    }
+                    writeln!(
+                        $f,
+                        "    {module}::reset_all({clock})?;",
+                        module = &cluster.name,
+                        clock = if $t.is_some() { "clock" } else { "" }
+                    )?;
+                }
+                Child::Register(index) => {
+                    let register = &$s.registers[index];
+                    if !register.is_resettable() {
+                        continue;
+                    }
+                    let features_cfg = features_cfg!(register).map(|features_cfg| indent(features_cfg, 1));
+                    for index in register.indices() {
+                        if let Some(features_cfg) = &features_cfg {
+                            write!($f, "{}", features_cfg)?;
+                        }
+                        writeln!(
+                            $f,
+                            "    {module}::try_{module}({index})?.reset().write();",
+                            module = &register.name,
+                            index = index
+                        )?;
+                    }
+                }
+            }
+        }
+        writeln!($f, "    Ok(())\n}}")?;
+    };
+}
+
+macro_rules! write_children_metadata {
+    ($s: ident, $f: ident) => {
+        if $s.clusters.is_empty() {
+            writeln!($f, "    clusters: &[],")?;
+        } else {
+            writeln!($f, "    clusters: &[")?;
+            for cluster in &$s.clusters {
+                if let Some(features_cfg) = features_cfg!(cluster) {
+                    write!($f, "{}", indent(features_cfg, 2))?;
+                }
+                writeln!($f, "        {module}::METADATA,", module = &cluster.name)?;
+            }
+            writeln!($f, "    ],")?;
+        }
+        if $s.registers.is_empty() {
+            writeln!($f, "    registers: &[],")?;
+        } else {
+            writeln!($f, "    registers: &[")?;
+            for register in &$s.registers {
+                if let Some(features_cfg) = features_cfg!(register) {
+                    write!($f, "{}", indent(features_cfg, 2))?;
+                }
+                writeln!(
+                    $f,
+                    "        <{register} as ral::Introspect>::METADATA,",
+                    register = register.name.to_case(Case::UpperCamel)
+                )?;
+            }
+            writeln!($f, "    ],")?;
+        }
+    };
+}
+
+macro_rules! write_optional_str {
+    ($f: ident, $k: expr, $v: expr) => {
+        match $v {
+            Some(value) => writeln!($f, "    {key}: Some(\"{value}\"),", key = $k, value = value)?,
+            None => writeln!($f, "    {key}: None,", key = $k)?,
+        }
+    };
+}
+
 macro_rules! write_access {
     ($f:ident, $a:expr, $i: expr) => {
         let access = $a.and_then(|access| match access {
@@ -181,3 +370,28 @@ macro_rules! write_access {
         }
     };
 }
+
+macro_rules! write_modified_write_values {
+    ($f:ident, $m:expr, $i: expr) => {
+        let modified_write_values =
+            $m.and_then(|modified_write_values| match modified_write_values {
+                ModifiedWriteValues::OneToClear => Some("oneToClear"),
+                ModifiedWriteValues::OneToSet => Some("oneToSet"),
+                ModifiedWriteValues::OneToToggle => Some("oneToToggle"),
+                ModifiedWriteValues::ZeroToClear => Some("zeroToClear"),
+                ModifiedWriteValues::ZeroToSet => Some("zeroToSet"),
+                ModifiedWriteValues::ZeroToToggle => Some("zeroToToggle"),
+                ModifiedWriteValues::Clear => Some("clear"),
+                ModifiedWriteValues::Set => Some("set"),
+                ModifiedWriteValues::Modify => None,
+            });
+        if let Some(modified_write_values) = modified_write_values {
+            writeln!(
+                $f,
+                "{indent}#[modified_write_values = \"{modified_write_values}\"]",
+                modified_write_values = modified_write_values,
+                indent = $i
+            )?;
+        }
+    };
+}
@@ -0,0 +1,198 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Generates sources for `device.svd` with optional overrides into fresh temporary directory
+fn try_generate(name: &str, overrides: Option<&str>) -> anyhow::Result<PathBuf> {
+    let dir = env::temp_dir().join("ral-gen-tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let svd = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/device.svd");
+    let overrides = overrides.map(|overrides| {
+        let file = dir.join("overrides.yml");
+        fs::write(&file, overrides).unwrap();
+        file
+    });
+    let out = dir.join("out");
+    ral_gen::generate(
+        svd.to_str().unwrap(),
+        overrides.as_ref().map(|file| file.to_str().unwrap()),
+        out.to_str().unwrap(),
+    )?;
+    Ok(out)
+}
+
+fn generate(name: &str, overrides: Option<&str>) -> PathBuf {
+    try_generate(name, overrides).unwrap()
+}
+
+fn read(out: &Path, file: &str) -> String {
+    fs::read_to_string(out.join("src").join(file)).unwrap()
+}
+
+#[test]
+fn test_metadata() {
+    let out = generate("metadata", None);
+
+    let lib = read(&out, "lib.rs");
+    assert!(lib.contains(
+        "pub const METADATA: ral::DeviceMetadata = ral::DeviceMetadata {\n    name: \"testdev\",\n    description: Some(\"Test device\"),\n"
+    ));
+    assert!(lib.contains(
+        "    peripherals: &[\n        peripherals::rcc::METADATA,\n        peripherals::gpioa::METADATA,\n        peripherals::tim2::METADATA,\n    ],\n"
+    ));
+
+    let tim2 = read(&out, "peripherals/tim2/mod.rs");
+    assert!(tim2.contains(
+        "pub const METADATA: ral::PeripheralMetadata = ral::PeripheralMetadata {\n    name: \"tim2\",\n    description: Some(\"Timer\"),\n    address: BASE_ADDRESS,\n"
+    ));
+    assert!(tim2.contains("    clusters: &[\n        dma::METADATA,\n    ],\n"));
+    assert!(tim2.contains(
+        "    registers: &[\n        <Cr1 as ral::Introspect>::METADATA,\n        <Cnt as ral::Introspect>::METADATA,\n        <Ccr as ral::Introspect>::METADATA,\n    ],\n"
+    ));
+
+    let dma = read(&out, "peripherals/tim2/dma/mod.rs");
+    assert!(dma.contains(
+        "pub const METADATA: ral::ClusterMetadata = ral::ClusterMetadata {\n    name: \"dma\",\n    description: Some(\"DMA cluster\"),\n"
+    ));
+    assert!(dma.contains("        <Dcr as ral::Introspect>::METADATA,\n"));
+}
+
+const CLOCK: &str = "peripherals:\n  TIM2:\n    clock: rcc.ahbenr.tim2en\n";
+
+#[test]
+fn test_clock() {
+    let out = generate("clock", Some(CLOCK));
+
+    let tim2 = read(&out, "peripherals/tim2/mod.rs");
+    assert!(tim2.contains("pub struct Clock(());\n"));
+    assert!(tim2.contains(
+        "pub fn try_enable_clock() -> Result<Clock, ral::BorrowError> {\n    super::rcc::try_ahbenr()?.read().set_tim2en().write();\n    Ok(Clock(()))\n}\n"
+    ));
+    assert!(tim2.contains("pub unsafe fn assume_enabled() -> Clock {\n"));
+    // Borrow functions are reachable only via the token
+    assert!(tim2.contains("mod cr1;\npub use cr1::Cr1;\n"));
+    assert!(!tim2.contains("pub use cr1::*;"));
+    assert!(tim2.contains(
+        "    pub fn try_cr1(&self) -> Result<Cr1, ral::BorrowError> {\n        cr1::try_cr1()\n    }\n"
+    ));
+    assert!(tim2.contains(
+        "    pub fn try_ccr(&self, index: usize) -> Result<Ccr, ral::BorrowError> {\n        ccr::try_ccr(index)\n    }\n"
+    ));
+
+    let dma = read(&out, "peripherals/tim2/dma/mod.rs");
+    assert!(dma.contains("impl super::Clock {\n"));
+    assert!(dma.contains(
+        "    pub fn try_dma_dcr(&self) -> Result<Dcr, ral::BorrowError> {\n        dcr::try_dcr()\n    }\n"
+    ));
+
+    let rcc = read(&out, "peripherals/rcc/mod.rs");
+    assert!(!rcc.contains("Clock"));
+}
+
+#[test]
+fn test_invalid_clock() {
+    let error = |name: &str, clock: &str| {
+        try_generate(name, Some(&CLOCK.replace("rcc.ahbenr.tim2en", clock)))
+            .err()
+            .unwrap()
+            .to_string()
+    };
+
+    assert_eq!(
+        error("clock_peripheral", "usart.cr1.en"),
+        "Clock usart.cr1.en of peripheral tim2 is invalid: peripheral usart not found"
+    );
+    assert_eq!(
+        error("clock_register", "rcc.apbenr.tim2en"),
+        "Clock rcc.apbenr.tim2en of peripheral tim2 is invalid: register apbenr not found"
+    );
+    assert_eq!(
+        error("clock_field", "rcc.ahbenr.tim3en"),
+        "Clock rcc.ahbenr.tim3en of peripheral tim2 is invalid: field tim3en not found"
+    );
+    assert_eq!(
+        error("clock_width", "gpioa.idr.idr0"),
+        "Clock gpioa.idr.idr0 of peripheral tim2 is invalid: field idr0 is not a single bit"
+    );
+    assert_eq!(
+        error("clock_gated", "tim2.cr1.cen"),
+        "Clock tim2.cr1.cen of peripheral tim2 is invalid: peripheral tim2 is itself clock gated"
+    );
+}
+
+#[test]
+fn test_reset_all() {
+    let out = generate("reset_all", None);
+    assert!(read(&out, "lib.rs").contains(
+        "pub fn reset_all() -> Result<(), ral::BorrowError> {\n    peripherals::rcc::reset_all()?;\n    peripherals::gpioa::reset_all()?;\n    peripherals::tim2::reset_all()?;\n    Ok(())\n}\n"
+    ));
+    // Read-only `idr` isn't reset
+    assert!(read(&out, "peripherals/gpioa/mod.rs").contains(
+        "pub fn reset_all() -> Result<(), ral::BorrowError> {\n    use ral::WritableRegister;\n    moder::try_moder()?.reset().write();\n    Ok(())\n}\n"
+    ));
+
+    let out = generate(
+        "reset_all_gated",
+        Some(&format!(
+            "{}    registers:\n      CNT:\n        read_action: clear\n",
+            CLOCK
+        )),
+    );
+    // Gated peripheral is reset only with its own clock token
+    assert!(read(&out, "lib.rs").contains(
+        "pub fn reset_all() -> Result<(), ral::BorrowError> {\n    peripherals::rcc::reset_all()?;\n    peripherals::gpioa::reset_all()?;\n    Ok(())\n}\n"
+    ));
+    // Clear-on-read `cnt` isn't reset
+    assert!(read(&out, "peripherals/tim2/mod.rs").contains(
+        "pub fn reset_all(clock: &Clock) -> Result<(), ral::BorrowError> {\n    use ral::WritableRegister;\n    cr1::try_cr1()?.reset().write();\n    ccr::try_ccr(0)?.reset().write();\n    ccr::try_ccr(1)?.reset().write();\n    dma::reset_all(clock)?;\n    Ok(())\n}\n"
+    ));
+    assert!(read(&out, "peripherals/tim2/dma/mod.rs").contains(
+        "pub fn reset_all(_clock: &super::Clock) -> Result<(), ral::BorrowError> {\n    use ral::WritableRegister;\n    dcr::try_dcr()?.reset().write();\n    Ok(())\n}\n"
+    ));
+}
+
+#[test]
+fn test_state() {
+    let out = generate("state", None);
+    let rcc = read(&out, "peripherals/rcc/mod.rs");
+    assert!(rcc.contains("pub struct PeripheralState {\n    ahbenr: u32,\n}\n"));
+    assert!(rcc.contains(
+        "pub fn save() -> Result<PeripheralState, ral::BorrowError> {\n    use ral::ReadableRegister;\n    Ok(PeripheralState {\n        ahbenr: try_ahbenr()?.read().get_bits(),\n    })\n}\n"
+    ));
+    assert!(rcc.contains(
+        "pub fn restore(state: &PeripheralState) -> Result<(), ral::BorrowError> {\n    use ral::WritableRegister;\n    try_ahbenr()?.restore_bits(state.ahbenr).write();\n    Ok(())\n}\n"
+    ));
+    // Read-only `idr` isn't saved
+    assert!(read(&out, "peripherals/gpioa/mod.rs")
+        .contains("pub struct PeripheralState {\n    moder: u32,\n}\n"));
+
+    let out = generate(
+        "state_gated",
+        Some(&format!(
+            "{}    state:\n      exclude:\n        - cnt\n      restore_order:\n        - dma.dcr\n",
+            CLOCK
+        )),
+    );
+    let tim2 = read(&out, "peripherals/tim2/mod.rs");
+    assert!(tim2.contains(
+        "pub struct PeripheralState {\n    dma_dcr: u16,\n    cr1: u32,\n    ccr: [u32; 2],\n}\n"
+    ));
+    assert!(tim2.contains(
+        "pub fn save(clock: &Clock) -> Result<PeripheralState, ral::BorrowError> {\n    use ral::ReadableRegister;\n    Ok(PeripheralState {\n        dma_dcr: clock.try_dma_dcr()?.read().get_bits(),\n        cr1: clock.try_cr1()?.read().get_bits(),\n        ccr: [clock.try_ccr(0)?.read().get_bits(), clock.try_ccr(1)?.read().get_bits()],\n    })\n}\n"
+    ));
+    assert!(tim2.contains(
+        "pub fn restore(clock: &Clock, state: &PeripheralState) -> Result<(), ral::BorrowError> {\n    use ral::WritableRegister;\n    clock.try_dma_dcr()?.restore_bits(state.dma_dcr).write();\n    clock.try_cr1()?.restore_bits(state.cr1).write();\n    clock.try_ccr(0)?.restore_bits(state.ccr[0]).write();\n    clock.try_ccr(1)?.restore_bits(state.ccr[1]).write();\n    Ok(())\n}\n"
+    ));
+
+    let error = try_generate(
+        "state_invalid",
+        Some("peripherals:\n  GPIOA:\n    state:\n      exclude:\n        - idr\n"),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "State register idr must be readable and writable register of the peripheral"
+    );
+}
@@ -0,0 +1,207 @@
+use core::mem::{size_of, MaybeUninit};
+use core::ops::{BitAnd, BitOr, Not};
+use core::ptr::{read_volatile, write_volatile};
+
+use crate::{Register, VolatileCell};
+
+/// Order of partial accesses to registers wider than bus access width
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessOrder {
+    /// Least significant part is accessed first
+    LowThenHigh,
+    /// Most significant part is accessed first
+    HighThenLow,
+}
+
+/// Byte order of register value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Same byte order as target's one
+    Native,
+    /// Least significant byte is stored at the lowest address
+    LittleEndian,
+    /// Most significant byte is stored at the lowest address
+    BigEndian,
+}
+
+/// How bits not covered by writable fields are written
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReservedBits {
+    /// Bits are written as cached, i.e. as they were read or reset
+    Preserve,
+    /// Bits are written as zeros
+    Zero,
+    /// Bits are written as in reset value
+    ResetValue,
+}
+
+/// Builds value to be written to the register from cached bits
+///
+/// Bits outside of [`Register::WRITABLE_MASK`] are replaced according to
+/// [`Register::RESERVED_BITS`] policy, and bits having side effect on write are replaced
+/// with no-op values unless explicitly modified
+#[inline]
+pub(crate) fn prepare_write<ValueType, RegisterType>(
+    bits: ValueType,
+    modified: ValueType,
+) -> ValueType
+where
+    ValueType:
+        Copy + Not<Output = ValueType> + BitAnd<Output = ValueType> + BitOr<Output = ValueType>,
+    RegisterType: Register<ValueType = ValueType>,
+{
+    let untouched = !modified;
+    let reserved = match RegisterType::RESERVED_BITS {
+        ReservedBits::Preserve => bits,
+        ReservedBits::Zero => bits & !bits,
+        ReservedBits::ResetValue => RegisterType::RESET_VALUE,
+    };
+    let bits = (!RegisterType::WRITABLE_MASK & reserved) | (RegisterType::WRITABLE_MASK & bits);
+    (bits & !(RegisterType::ONE_TO_MODIFY_MASK & untouched))
+        | (RegisterType::ZERO_TO_MODIFY_MASK & untouched)
+}
+
+/// Loads value from the register, splitting access according to register access width and order
+/// and converting it from register byte order
+#[inline]
+pub(crate) unsafe fn load<ValueType, RegisterType>(
+    register: *const VolatileCell<ValueType>,
+) -> ValueType
+where
+    ValueType: Copy,
+    RegisterType: Register<ValueType = ValueType>,
+{
+    #[cfg(feature = "simulation")]
+    if let Some(value) = crate::simulation::load(register) {
+        return value;
+    }
+    let little_endian = is_little_endian::<RegisterType>();
+    let order = RegisterType::ACCESS_ORDER;
+    let value = match split_width::<ValueType, RegisterType>() {
+        8 => split_load::<ValueType, u8>(register, order, little_endian),
+        16 => split_load::<ValueType, u16>(register, order, little_endian),
+        32 => split_load::<ValueType, u32>(register, order, little_endian),
+        _ => (*register).get(),
+    };
+    if little_endian == cfg!(target_endian = "little") {
+        value
+    } else {
+        swap_bytes(value)
+    }
+}
+
+/// Stores value to the register, converting it to register byte order
+/// and splitting access according to register access width and order
+#[inline]
+pub(crate) unsafe fn store<ValueType, RegisterType>(
+    register: *mut VolatileCell<ValueType>,
+    value: ValueType,
+) where
+    ValueType: Copy,
+    RegisterType: Register<ValueType = ValueType>,
+{
+    #[cfg(feature = "simulation")]
+    if crate::simulation::store(register, value) {
+        return;
+    }
+    let little_endian = is_little_endian::<RegisterType>();
+    let order = RegisterType::ACCESS_ORDER;
+    let value = if little_endian == cfg!(target_endian = "little") {
+        value
+    } else {
+        swap_bytes(value)
+    };
+    match split_width::<ValueType, RegisterType>() {
+        8 => split_store::<ValueType, u8>(register, value, order, little_endian),
+        16 => split_store::<ValueType, u16>(register, value, order, little_endian),
+        32 => split_store::<ValueType, u32>(register, value, order, little_endian),
+        _ => (*register).set(value),
+    }
+}
+
+/// Returns width of partial access in bits or `0` if the register is accessed at once
+#[inline]
+fn split_width<ValueType, RegisterType>() -> u32
+where
+    RegisterType: Register<ValueType = ValueType>,
+{
+    if (RegisterType::ACCESS_WIDTH as usize) < size_of::<ValueType>() * 8 {
+        RegisterType::ACCESS_WIDTH
+    } else {
+        0
+    }
+}
+
+/// Whether register value is stored in little endian byte order
+#[inline]
+fn is_little_endian<RegisterType: Register>() -> bool {
+    match RegisterType::BYTE_ORDER {
+        ByteOrder::Native => cfg!(target_endian = "little"),
+        ByteOrder::LittleEndian => true,
+        ByteOrder::BigEndian => false,
+    }
+}
+
+/// Reverses bytes of the value
+#[inline]
+fn swap_bytes<ValueType: Copy>(value: ValueType) -> ValueType {
+    let mut swapped = value;
+    unsafe {
+        core::slice::from_raw_parts_mut(
+            &mut swapped as *mut ValueType as *mut u8,
+            size_of::<ValueType>(),
+        )
+        .reverse();
+    }
+    swapped
+}
+
+/// Index of the part in memory, `part` is counted from the least significant one
+#[inline]
+fn part_index(part: usize, count: usize, little_endian: bool) -> usize {
+    if little_endian {
+        part
+    } else {
+        count - 1 - part
+    }
+}
+
+/// Part to be accessed at specific step
+#[inline]
+fn part_at(step: usize, count: usize, order: AccessOrder) -> usize {
+    match order {
+        AccessOrder::LowThenHigh => step,
+        AccessOrder::HighThenLow => count - 1 - step,
+    }
+}
+
+unsafe fn split_load<ValueType: Copy, PartType: Copy>(
+    register: *const VolatileCell<ValueType>,
+    order: AccessOrder,
+    little_endian: bool,
+) -> ValueType {
+    let count = size_of::<ValueType>() / size_of::<PartType>();
+    let source = register as *const PartType;
+    let mut value = MaybeUninit::<ValueType>::uninit();
+    let target = value.as_mut_ptr() as *mut PartType;
+    for step in 0..count {
+        let index = part_index(part_at(step, count, order), count, little_endian);
+        target.add(index).write(read_volatile(source.add(index)));
+    }
+    value.assume_init()
+}
+
+unsafe fn split_store<ValueType: Copy, PartType: Copy>(
+    register: *mut VolatileCell<ValueType>,
+    value: ValueType,
+    order: AccessOrder,
+    little_endian: bool,
+) {
+    let count = size_of::<ValueType>() / size_of::<PartType>();
+    let target = register as *mut PartType;
+    let source = &value as *const ValueType as *const PartType;
+    for step in 0..count {
+        let index = part_index(part_at(step, count, order), count, little_endian);
+        write_volatile(target.add(index), source.add(index).read());
+    }
+}
@@ -0,0 +1,183 @@
+use core::marker::PhantomData;
+use core::ops::{BitAnd, BitOr, Not};
+
+use crate::access::prepare_write;
+use crate::{Cache, Register};
+
+/// Transport to registers of external device, e.g. chip connected via I2C or SPI
+///
+/// Values are passed as `u64` regardless of register width,
+/// encoding them on the wire (including byte order) is up to the implementation
+pub trait Transport {
+    /// Transport error, returned by fallible register `read()` and `write()`
+    type Error;
+
+    /// Reads `width` bits wide register located at `address`
+    fn read(&mut self, address: usize, width: u32) -> Result<u64, Self::Error>;
+
+    /// Writes `width` bits wide register located at `address`
+    fn write(&mut self, address: usize, width: u32, value: u64) -> Result<(), Self::Error>;
+}
+
+/// Register value which can be passed through [`Transport`]
+pub trait TransportValue: Copy {
+    /// Builds value from transport bits, extra high bits are discarded
+    fn from_transport(bits: u64) -> Self;
+
+    /// Converts value to transport bits
+    fn into_transport(self) -> u64;
+}
+
+macro_rules! transport_value {
+    ($($t: ty),*) => {
+        $(
+            impl TransportValue for $t {
+                #[inline]
+                fn from_transport(bits: u64) -> Self {
+                    bits as $t
+                }
+
+                #[inline]
+                fn into_transport(self) -> u64 {
+                    self as u64
+                }
+            }
+        )*
+    };
+}
+
+transport_value!(u8, u16, u32, u64);
+
+/// Register data holder for registers accessed via [`Transport`]
+pub struct BusR<ValueType, RegisterType>
+where
+    ValueType: TransportValue
+        + Not<Output = ValueType>
+        + BitAnd<Output = ValueType>
+        + BitOr<Output = ValueType>,
+    RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
+{
+    register_type: PhantomData<RegisterType>,
+    address: usize,
+    bits: ValueType,
+    modified: ValueType,
+}
+
+impl<ValueType, RegisterType> BusR<ValueType, RegisterType>
+where
+    ValueType: TransportValue
+        + Not<Output = ValueType>
+        + BitAnd<Output = ValueType>
+        + BitOr<Output = ValueType>,
+    RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
+{
+    /// Creates new instance bound to register at specific address of external device
+    #[inline]
+    pub fn new(address: usize) -> Self {
+        Self {
+            register_type: PhantomData,
+            address,
+            bits: RegisterType::RESET_VALUE,
+            modified: RegisterType::RESET_VALUE & !RegisterType::RESET_VALUE,
+        }
+    }
+
+    /// Gets register address
+    #[inline]
+    pub fn address(&self) -> usize {
+        self.address
+    }
+
+    /// Gets cached bits
+    #[inline]
+    pub fn get_bits(&self) -> ValueType {
+        self.bits
+    }
+
+    /// Stores bits into cache, all bits are considered explicitly modified
+    #[inline]
+    pub fn set_bits(&mut self, bits: ValueType) {
+        self.bits = bits;
+        self.modified = bits | !bits;
+    }
+
+    /// Stores bits selected by mask into cache, selected bits are considered explicitly modified
+    #[inline]
+    pub fn update_bits(&mut self, mask: ValueType, bits: ValueType) {
+        self.bits = (self.bits & !mask) | (bits & mask);
+        self.modified = self.modified | mask;
+    }
+
+    /// Loads data from register to cache, cache is left intact on error
+    #[inline]
+    pub fn read<T: Transport>(&mut self, transport: &mut T) -> Result<(), T::Error> {
+        let bits = transport.read(self.address, Self::width())?;
+        self.bits = ValueType::from_transport(bits);
+        self.modified = self.modified & !self.modified;
+        Ok(())
+    }
+
+    /// Stores data from cache to register, the same way as [`crate::R::write`] does,
+    /// modified bits are kept on error, so write can be retried
+    #[inline]
+    pub fn write<T: Transport>(&mut self, transport: &mut T) -> Result<(), T::Error> {
+        let bits = prepare_write::<ValueType, RegisterType>(self.bits, self.modified);
+        transport.write(self.address, Self::width(), bits.into_transport())?;
+        self.modified = self.modified & !self.modified;
+        Ok(())
+    }
+
+    /// Takes snapshot of the cache
+    #[inline]
+    pub fn save_cache(&self) -> Cache<ValueType> {
+        Cache {
+            bits: self.bits,
+            modified: self.modified,
+        }
+    }
+
+    /// Restores the cache from snapshot
+    #[inline]
+    pub fn restore_cache(&mut self, cache: Cache<ValueType>) {
+        self.bits = cache.bits;
+        self.modified = cache.modified;
+    }
+
+    #[inline]
+    fn width() -> u32 {
+        (core::mem::size_of::<ValueType>() * 8) as u32
+    }
+}
+
+/// Trait representing readable part of register accessed via [`Transport`]
+pub trait ReadableBusRegister: Register {
+    /// Get value of the register as raw bits
+    fn get_bits(&self) -> Self::ValueType;
+
+    /// Loads value from the register
+    fn read<T: Transport>(
+        &mut self,
+        transport: &mut T,
+    ) -> Result<&mut Self::RegisterType, T::Error>;
+}
+
+/// Trait representing writable part of register accessed via [`Transport`]
+pub trait WritableBusRegister: Register {
+    /// Set value of the register as raw bits
+    fn set_bits(&mut self, bits: Self::ValueType) -> &mut Self::RegisterType;
+
+    /// Reset value of the register to default
+    fn reset(&mut self) -> &mut Self::RegisterType;
+
+    /// Writes value to the register
+    fn write<T: Transport>(
+        &mut self,
+        transport: &mut T,
+    ) -> Result<&mut Self::RegisterType, T::Error>;
+
+    /// Takes snapshot of the cached value
+    fn save_cache(&self) -> Cache<Self::ValueType>;
+
+    /// Restores cached value from snapshot
+    fn restore_cache(&mut self, cache: Cache<Self::ValueType>) -> &mut Self::RegisterType;
+}
@@ -0,0 +1,198 @@
+use core::marker::PhantomData;
+use core::ops::{Add, BitAnd, BitOr, Not, Shl, Shr};
+
+/// Register field description, produces [`FieldValue`] for specific value
+pub struct Field<ValueType, RegisterType> {
+    register_type: PhantomData<RegisterType>,
+    mask: ValueType,
+    offset: u32,
+}
+
+impl<ValueType: Copy, RegisterType> Field<ValueType, RegisterType> {
+    /// Creates field of bits selected by `mask` (not shifted) starting from `offset`
+    pub const fn new(mask: ValueType, offset: u32) -> Self {
+        Field {
+            register_type: PhantomData,
+            mask,
+            offset,
+        }
+    }
+}
+
+impl<ValueType, RegisterType> Field<ValueType, RegisterType>
+where
+    ValueType: Copy
+        + BitAnd<Output = ValueType>
+        + Shl<u32, Output = ValueType>
+        + Shr<u32, Output = ValueType>,
+{
+    /// Field bits in place
+    #[inline]
+    pub fn mask(&self) -> ValueType {
+        self.mask << self.offset
+    }
+
+    /// Offset of the field's least significant bit
+    #[inline]
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Produces field value, extra high bits of `value` are discarded
+    #[inline]
+    pub fn val(&self, value: ValueType) -> FieldValue<ValueType, RegisterType> {
+        FieldValue::new(self.mask(), (value & self.mask) << self.offset)
+    }
+
+    /// Extracts field value from register bits
+    #[inline]
+    pub fn read(&self, bits: ValueType) -> ValueType {
+        (bits >> self.offset) & self.mask
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Clone for Field<ValueType, RegisterType> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Copy for Field<ValueType, RegisterType> {}
+
+/// Values of one or several fields of the register, combined with `+` or `|`
+/// ```no_run
+/// # use ral::{ReadableRegister, WritableRegister};
+/// #
+/// # ral::peripheral! {
+/// #     tim2 {
+/// #         base_address: 0x4000_0000,
+/// #         cr1 {
+/// #             offset: 0x0,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 cms[5:2] as u8,
+/// #                 cen[0:1] as bool,
+/// #             }
+/// #         },
+/// #         dier {
+/// #             offset: 0xC,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uie[0:1] as bool,
+/// #             }
+/// #         },
+/// #         sr {
+/// #             offset: 0x10,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uif[0:1] as bool,
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # fn main() {
+/// # use tim2::Cr1;
+/// let mut cr1 = tim2::cr1().unwrap();
+/// cr1.modify(Cr1::CMS.val(0b01) + Cr1::CEN_SET).write();
+/// assert!(cr1.read().matches_all(Cr1::CMS.val(0b01) + Cr1::CEN_SET));
+/// # }
+/// ```
+pub struct FieldValue<ValueType, RegisterType> {
+    register_type: PhantomData<RegisterType>,
+    mask: ValueType,
+    value: ValueType,
+}
+
+impl<ValueType: Copy, RegisterType> FieldValue<ValueType, RegisterType> {
+    /// Creates field value of bits selected by `mask`, both `mask` and `value` are in place
+    pub const fn new(mask: ValueType, value: ValueType) -> Self {
+        FieldValue {
+            register_type: PhantomData,
+            mask,
+            value,
+        }
+    }
+
+    /// Bits of the fields
+    #[inline]
+    pub fn mask(&self) -> ValueType {
+        self.mask
+    }
+
+    /// Value of the fields in place
+    #[inline]
+    pub fn value(&self) -> ValueType {
+        self.value
+    }
+}
+
+impl<ValueType, RegisterType> FieldValue<ValueType, RegisterType>
+where
+    ValueType: Copy
+        + PartialEq
+        + Not<Output = ValueType>
+        + BitAnd<Output = ValueType>
+        + BitOr<Output = ValueType>,
+{
+    /// Applies field values to register bits
+    #[inline]
+    pub fn modify(&self, bits: ValueType) -> ValueType {
+        (bits & !self.mask) | self.value
+    }
+
+    /// Checks whether all fields of register bits have specified values
+    #[inline]
+    pub fn matches_all(&self, bits: ValueType) -> bool {
+        bits & self.mask == self.value
+    }
+
+    /// Checks whether any bit set in specified values is set in register bits
+    #[inline]
+    pub fn matches_any(&self, bits: ValueType) -> bool {
+        let zero = bits & !bits;
+        bits & self.mask & self.value != zero
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Clone for FieldValue<ValueType, RegisterType> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Copy for FieldValue<ValueType, RegisterType> {}
+
+impl<ValueType, RegisterType> BitOr for FieldValue<ValueType, RegisterType>
+where
+    ValueType: Copy + BitOr<Output = ValueType>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        FieldValue {
+            register_type: PhantomData,
+            mask: self.mask | rhs.mask,
+            value: self.value | rhs.value,
+        }
+    }
+}
+
+impl<ValueType, RegisterType> Add for FieldValue<ValueType, RegisterType>
+where
+    ValueType: Copy + BitOr<Output = ValueType>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self {
+        BitOr::bitor(self, rhs)
+    }
+}
@@ -0,0 +1,215 @@
+#[cfg(feature = "borrow-tracking")]
+use core::cell::Cell;
+use core::fmt::{Display, Formatter};
+use core::panic::Location;
+use core::sync::atomic::Ordering::SeqCst;
+use core::sync::atomic::{AtomicBool, AtomicPtr, AtomicUsize};
+
+#[cfg(feature = "borrow-tracking")]
+use critical_section::Mutex;
+
+use crate::VolatileCell;
+
+/// Holder of the register pointer, the register can be borrowed from it only once at a time
+///
+/// With `borrow-tracking` feature enabled also records location the register was borrowed at
+pub struct RegisterHolder<ValueType> {
+    register: AtomicPtr<VolatileCell<ValueType>>,
+    base: Option<&'static RuntimeBase>,
+    borrowed: AtomicBool,
+    #[cfg(feature = "borrow-tracking")]
+    borrowed_at: Mutex<Cell<Option<&'static Location<'static>>>>,
+}
+
+impl<ValueType> RegisterHolder<ValueType> {
+    /// Creates holder of the register located at `register`, intended to be used for `static` declarations
+    pub const fn new(register: *mut VolatileCell<ValueType>) -> Self {
+        RegisterHolder {
+            register: AtomicPtr::new(register),
+            base: None,
+            borrowed: AtomicBool::new(false),
+            #[cfg(feature = "borrow-tracking")]
+            borrowed_at: Mutex::new(Cell::new(None)),
+        }
+    }
+
+    /// Creates holder of the register located at `offset` relative to `base` set at runtime
+    pub const fn relative(offset: usize, base: &'static RuntimeBase) -> Self {
+        RegisterHolder {
+            register: AtomicPtr::new(offset as *mut VolatileCell<ValueType>),
+            base: Some(base),
+            borrowed: AtomicBool::new(false),
+            #[cfg(feature = "borrow-tracking")]
+            borrowed_at: Mutex::new(Cell::new(None)),
+        }
+    }
+
+    /// Gets register pointer, `None` if runtime base address is not set yet
+    fn register(&self) -> Option<*mut VolatileCell<ValueType>> {
+        let register = self.register.load(SeqCst);
+        match self.base {
+            Some(base) => base
+                .get()
+                .map(|base| (base + register as usize) as *mut VolatileCell<ValueType>),
+            None => Some(register),
+        }
+    }
+
+    /// Gets location the register is currently borrowed at,
+    /// always `None` unless `borrow-tracking` feature is enabled
+    pub fn who_holds(&self) -> Option<&'static Location<'static>> {
+        #[cfg(feature = "borrow-tracking")]
+        {
+            critical_section::with(|cs| self.borrowed_at.borrow(cs).get())
+        }
+        #[cfg(not(feature = "borrow-tracking"))]
+        {
+            None
+        }
+    }
+
+    /// Takes the register pointer out of the holder on behalf of `location`
+    pub(crate) fn take(
+        &self,
+        location: &'static Location<'static>,
+    ) -> Result<*mut VolatileCell<ValueType>, BorrowError> {
+        let register = self.register().ok_or(BorrowError {
+            kind: BorrowErrorKind::BaseNotSet,
+            held_at: None,
+        })?;
+        #[cfg(feature = "borrow-tracking")]
+        {
+            critical_section::with(|cs| {
+                let borrowed_at = self.borrowed_at.borrow(cs);
+                if self.borrowed.swap(true, SeqCst) {
+                    Err(BorrowError {
+                        kind: BorrowErrorKind::Borrowed,
+                        held_at: borrowed_at.get(),
+                    })
+                } else {
+                    borrowed_at.set(Some(location));
+                    Ok(register)
+                }
+            })
+        }
+        #[cfg(not(feature = "borrow-tracking"))]
+        {
+            let _ = location;
+            if self.borrowed.swap(true, SeqCst) {
+                Err(BorrowError {
+                    kind: BorrowErrorKind::Borrowed,
+                    held_at: None,
+                })
+            } else {
+                Ok(register)
+            }
+        }
+    }
+
+    /// Marks the register as returned to the holder
+    pub(crate) fn put(&self) {
+        #[cfg(feature = "borrow-tracking")]
+        {
+            critical_section::with(|cs| {
+                self.borrowed_at.borrow(cs).set(None);
+                self.borrowed.store(false, SeqCst);
+            })
+        }
+        #[cfg(not(feature = "borrow-tracking"))]
+        {
+            self.borrowed.store(false, SeqCst);
+        }
+    }
+}
+
+/// Base address of registers provided at runtime, e.g. address PCIe BAR is mapped at
+pub struct RuntimeBase {
+    address: AtomicUsize,
+    is_set: AtomicBool,
+}
+
+impl RuntimeBase {
+    /// Creates base address which is not set yet, intended to be used for `static` declarations
+    pub const fn new() -> Self {
+        RuntimeBase {
+            address: AtomicUsize::new(0),
+            is_set: AtomicBool::new(false),
+        }
+    }
+
+    /// Sets base address, registers borrowed before keep previous address until returned
+    pub fn set(&self, address: usize) {
+        self.address.store(address, SeqCst);
+        self.is_set.store(true, SeqCst);
+    }
+
+    /// Gets base address, `None` if it's not set yet
+    pub fn get(&self) -> Option<usize> {
+        if self.is_set.load(SeqCst) {
+            Some(self.address.load(SeqCst))
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for RuntimeBase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Reason the register couldn't be borrowed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BorrowErrorKind {
+    /// Register is already borrowed elsewhere
+    Borrowed,
+    /// Index is out of bounds of the register array
+    OutOfBounds,
+    /// Runtime base address of the register is not set yet
+    BaseNotSet,
+}
+
+/// Error returned when the register can't be borrowed, e.g. it's already borrowed
+#[derive(Clone, Copy, Debug)]
+pub struct BorrowError {
+    kind: BorrowErrorKind,
+    held_at: Option<&'static Location<'static>>,
+}
+
+impl BorrowError {
+    /// Creates error for index out of bounds of the register array
+    pub const fn out_of_bounds() -> Self {
+        BorrowError {
+            kind: BorrowErrorKind::OutOfBounds,
+            held_at: None,
+        }
+    }
+
+    /// Gets reason the register couldn't be borrowed
+    pub fn kind(&self) -> BorrowErrorKind {
+        self.kind
+    }
+
+    /// Gets location the register is borrowed at,
+    /// always `None` unless `borrow-tracking` feature is enabled
+    pub fn who_holds(&self) -> Option<&'static Location<'static>> {
+        self.held_at
+    }
+}
+
+impl Display for BorrowError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match (self.kind, self.held_at) {
+            (BorrowErrorKind::OutOfBounds, _) => write!(f, "Register index is out of bounds"),
+            (BorrowErrorKind::BaseNotSet, _) => {
+                write!(f, "Runtime base address of the register is not set")
+            }
+            (BorrowErrorKind::Borrowed, Some(location)) => {
+                write!(f, "Register is already borrowed at {}", location)
+            }
+            (BorrowErrorKind::Borrowed, None) => write!(f, "Register is already borrowed"),
+        }
+    }
+}
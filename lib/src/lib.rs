@@ -10,10 +10,39 @@
 //!
 //! Internally generates register description backed by [ral](https://docs.rs/ral)
 //! ```
+//! # pub mod types {
+//! #     #[derive(Clone, Copy, Debug, PartialEq)]
+//! #     pub enum CustomType {
+//! #         A,
+//! #         B,
+//! #     }
+//! #
+//! #     impl core::convert::TryFrom<u32> for CustomType {
+//! #         type Error = u32;
+//! #
+//! #         fn try_from(value: u32) -> Result<Self, Self::Error> {
+//! #             match value {
+//! #                 0 => Ok(CustomType::A),
+//! #                 1 => Ok(CustomType::B),
+//! #                 _ => Err(value),
+//! #             }
+//! #         }
+//! #     }
+//! #
+//! #     impl From<CustomType> for u32 {
+//! #         fn from(value: CustomType) -> Self {
+//! #             value as u32
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
 //! use ral::register;
 //!
 //! register! {
-//!     use crate_name::types::CustomType; // Use expressions for custom types used in field declarations
+//!     use crate::types::CustomType; // Use expressions for custom types used in field declarations
 //!
 //!     #[access = "read-write"] // Optional register wide access specifier
 //!     #[doc = "Register description"] // Optional register description
@@ -48,34 +77,64 @@
 //!         }
 //!     }
 //! }
+//! # }
+//! #
+//! # fn main() {
+//! #     let mut reg0 = reg0::reg0().unwrap();
+//! #     reg0.set_field0(0x12).set_field2();
+//! #     assert_eq!(reg0.get_field0(), 0x12);
+//! #     assert!(reg0::reg0().is_none());
+//! # }
 //! ```
 //!
+//! Bits of a field can also be specified datasheet style as `<name>[<msb>..=<lsb>]`,
+//! e.g. `field5[31..=16] as u16` is the same as `field5[16:16] as u16`
+//!
+//! Definition is validated at compile time, fields must fit in `value_size` and not overlap each other,
+//! `reset_mask` and `reset_value` must fit in `value_size` and reset value must not set bits outside of reset mask
+//!
 //! Above register definition will be transformed into following code
 //! ```
+//! # pub mod types {
+//! #     pub type CustomType = u8;
+//! # }
+//! #
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
 //! // Required uses section
-//! use core::sync::atomic::AtomicPtr;
 //! use core::convert::TryFrom;
 //! use ral::{borrow_register, init_register, return_register, value_read, value_write, R, ReadableRegister, Register, VolatileCell, WritableRegister};
-//! use crate_name::types::CustomType;
-//! const REGISTER: AtomicPtr<VolatileCell<<Reg0 as Register>::ValueType>> = AtomicPtr::new(
-//!     (super::BASE_ADDRESS /* Enclosing peripheral/cluster base address */ + 0x00 /* offset */) as *mut VolatileCell<<Reg0 as Register>::ValueType>,
+//! use crate::types::CustomType;
+//! static REGISTER: ral::RegisterHolder<<Reg0 as Register>::ValueType> = ral::RegisterHolder::new(
+//!     <Reg0 as Register>::ADDRESS as *mut VolatileCell<<Reg0 as Register>::ValueType>,
 //! );
 //! ///Register description
+//! #[track_caller]
 //! pub fn reg0() -> Option<Reg0> {
 //!     borrow_register(&REGISTER).map(Reg0)
 //! }
+//! ///Register description
+//! #[track_caller]
+//! pub fn try_reg0() -> Result<Reg0, ral::BorrowError> {
+//!     ral::try_borrow_register(&REGISTER).map(Reg0)
+//! }
 //! pub struct Reg0(R<u32, Reg0>);
 //! impl Drop for Reg0 {
 //!     fn drop(&mut self) {
 //!         let Reg0(register) = self;
-//!         return_register(&REGISTER, register);
+//!         return_register(&REGISTER);
 //!     }
 //! }
 //! impl Register for Reg0 {
 //!     type RegisterType = Self;
 //!     type ValueType = u32;
+//!     const ADDRESS: usize = super::BASE_ADDRESS /* Enclosing peripheral/cluster base address */ + 0x08 /* offset */;
 //!     const RESET_MASK: Self::ValueType = 0xFFFF_FFFF;
 //!     const RESET_VALUE: Self::ValueType = 0x1234_0000;
+//!     const WRITABLE_MASK: Self::ValueType = 0x0000_C7FF; // Bits of writable fields
+//!     const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000; // Bits of `oneTo*` fields
+//!     const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000; // Bits of `zeroTo*` fields
 //! }
 //! impl ReadableRegister for Reg0 { // Will be added if register wide access allows read
 //!     fn get_bits(&self) -> Self::ValueType {
@@ -99,8 +158,39 @@
 //!         self.0.write();
 //!         self
 //!     }
+//!     fn save_cache(&self) -> ral::Cache<Self::ValueType> {
+//!         self.0.save_cache()
+//!     }
+//!     fn restore_cache(&mut self, cache: ral::Cache<Self::ValueType>) -> &mut Self::RegisterType {
+//!         self.0.restore_cache(cache);
+//!         self
+//!     }
+//! }
+//! impl Reg0 {
+//!     /// Gets pointer to the register, e.g. for DMA configuration
+//!     #[inline]
+//!     pub fn as_ptr(&self) -> *mut u32 {
+//!         self.0.as_ptr() as *mut u32
+//!     }
+//!     /// Gets location the register is currently borrowed at,
+//!     /// requires `borrow-tracking` feature of `ral`
+//!     pub fn who_holds() -> Option<&'static core::panic::Location<'static>> {
+//!         REGISTER.who_holds()
+//!     }
+//! }
+//! impl Reg0 {
+//!     /// Applies field values to cached bits
+//!     #[inline]
+//!     pub fn modify(&mut self, value: ral::FieldValue<u32, Self>) -> &mut Self {
+//!         self.0.update_bits(value.mask(), value.value());
+//!         self
+//!     }
+//!     // Also `matches_all(value)` and `matches_any(value)` checks of cached bits
 //! }
 //! impl Reg0 { // Will be added if any fields specified
+//!     pub const FIELD5: ral::Field<u32, Self> = ral::Field::new(0x0000FFFFu32, 16); // Field descriptor
+//!     pub const FIELD5_MASK: u32 = 0x0000FFFFu32 << 16; // Field bits in place
+//!     pub const FIELD5_OFFSET: u32 = 16;
 //!     ///Bits 16:31 - Read-only u16 field
 //!     #[inline]
 //!     pub fn get_field5(&self) -> u16 {
@@ -113,9 +203,9 @@
 //!     pub fn set_field4(&mut self, value: u8) -> &mut Self {
 //!         value_write!(self, 0x00000003u32, 14, value as <Self as Register>::ValueType);
 //!         // Will further expand into
-//!         // self.0.set_bits(
-//!         //     (self.0.get_bits() & !(0x00000003u32 << 14))
-//!         //         | ((value as <Self as Register>::ValueType & 0x00000003u32) << 14),
+//!         // self.0.update_bits(
+//!         //     0x00000003u32 << 14,
+//!         //     (value as <Self as Register>::ValueType & 0x00000003u32) << 14,
 //!         // );
 //!         self
 //!     }
@@ -138,9 +228,9 @@
 //!     pub fn set_field2_value(&mut self, value: bool) -> &mut Self {
 //!         value_write!(self, 0x00000001u32, 10, value as <Self as Register>::ValueType);
 //!         // Will further expand into
-//!         // self.0.set_bits(
-//!         //     (self.0.get_bits() & !(0x00000001u32 << 10))
-//!         //         | ((value as <Self as Register>::ValueType & 0x00000001u32) << 10),
+//!         // self.0.update_bits(
+//!         //     0x00000001u32 << 10,
+//!         //     (value as <Self as Register>::ValueType & 0x00000001u32) << 10,
 //!         // );
 //!         self
 //!     }
@@ -172,12 +262,12 @@
 //!         value: CustomType,
 //!     ) -> Result<&mut Self, <<Self as Register>::ValueType as TryFrom<CustomType>>::Error>
 //!     {
-//!         value_write!(self, 0x00000003u32, 8, <<Self as Register>::ValueType as TryFrom<#ty>>::try_from(value)?);
+//!         value_write!(self, 0x00000003u32, 8, <<Self as Register>::ValueType as TryFrom<CustomType>>::try_from(value)?);
 //!         // Will further expand into
-//!         // self.0.set_bits(
-//!         //     (self.0.get_bits() & !(0x00000003u32 << 8))
-//!         //         | ((<<Self as Register>::ValueType as TryFrom<CustomType>>::try_from(value)?
-//!         //             & 0x00000003u32) << 8),
+//!         // self.0.update_bits(
+//!         //     0x00000003u32 << 8,
+//!         //     (<<Self as Register>::ValueType as TryFrom<CustomType>>::try_from(value)?
+//!         //         & 0x00000003u32) << 8,
 //!         // );
 //!         Ok(self)
 //!     }
@@ -193,13 +283,16 @@
 //!     pub fn set_field0(&mut self, value: u8) -> &mut Self {
 //!         value_write!(self, 0x000000FFu32, 0, value as <Self as Register>::ValueType);
 //!         // Will further expand into
-//!         // self.0.set_bits(
-//!         //     (self.0.get_bits() & !(0x000000FFu32 << 0))
-//!         //         | ((value as <Self as Register>::ValueType & 0x000000FFu32) << 0),
+//!         // self.0.update_bits(
+//!         //     0x000000FFu32 << 0,
+//!         //     (value as <Self as Register>::ValueType & 0x000000FFu32) << 0,
 //!         // );
 //!         self
 //!     }
 //! }
+//! # }
+//! #
+//! # fn main() {}
 //! ```
 //!
 //! # Proposed modules structure
@@ -211,7 +304,7 @@
 //! mod reg0;
 //! pub use reg0::*;
 //!
-//! const BASE_ADDRESS: usize = 0x0000_0000; // Should be actual peripheral base address
+//! pub const BASE_ADDRESS: usize = 0x0000_0000; // Should be actual peripheral base address
 //! ```
 //!
 //! Peripherals module should look like this
@@ -254,22 +347,710 @@
 //! mod reg4;
 //! pub use reg4::*;
 //!
-//! const BASE_ADDRESS: usize = super::BASE_ADDRESS + 0x40; // Enclosing peripheral/cluster base address plus offset
+//! pub const BASE_ADDRESS: usize = super::BASE_ADDRESS + 0x40; // Enclosing peripheral/cluster base address plus offset
+//! ```
+//!
+//! # Peripheral macro
+//!
+//! Small projects and external chips can describe whole peripheral in one place with `peripheral!` macro,
+//! which generates the module structure above, `reset_all()` function and `METADATA` table
+//! ```no_run
+//! # pub mod types {
+//! #     pub type CustomType = u8;
+//! # }
+//! #
+//! use ral::peripheral;
+//!
+//! peripheral! {
+//!     use crate::types::CustomType; // Use expressions shared by all registers, paths must be absolute
+//!
+//!     #[doc = "Peripheral description"]
+//!     peripheral { // Name of generated module
+//!         base_address: 0x4000_0000,
+//!         reg0 { // Registers are declared the same way as in `register!` macro
+//!             offset: 0x0,
+//!             value_size: 32,
+//!             reset_mask: 0xFFFFFFFF,
+//!             reset_value: 0x0,
+//!             fields: {
+//!                 field0[0:8] as CustomType,
+//!             }
+//!         },
+//!         #[doc = "Cluster description"]
+//!         cluster cluster { // Clusters are declared with `cluster` keyword and can be nested
+//!             offset: 0x40,
+//!             reg2 {
+//!                 offset: 0x0,
+//!                 value_size: 16,
+//!                 reset_mask: 0xFFFF,
+//!                 reset_value: 0x0,
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let reg2 = peripheral::cluster::reg2().unwrap();
+//! # }
+//! ```
+//!
+//! # Base address
+//!
+//! Register address is offset from `super::BASE_ADDRESS` by default, other base can be specified with `base` key
+//! taking any constant expression, so registers aren't bound to the modules structure
+//! ```no_run
+//! # pub mod dma1 {
+//! #     pub const BASE_ADDRESS: usize = 0x4002_0000;
+//! # }
+//! #
+//! # pub mod cr {
+//! # use ral::register;
+//! #
+//! # register! {
+//! cr {
+//!     base: crate::dma1::BASE_ADDRESS + 0x100, // Or just path, e.g. `crate::memory_map::DMA1_CH1`
+//!     offset: 0x08,
+//!     // ...
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0
+//! }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
 //! ```
+//! Registers of relocatable blocks, e.g. mapped through PCIe BAR, use base address provided at runtime with
+//! `runtime_base` key referring to [`RuntimeBase`] static, `Register::ADDRESS` is then relative to it
+//! ```no_run
+//! pub static BAR0: ral::RuntimeBase = ral::RuntimeBase::new();
+//!
+//! # pub mod ctrl {
+//! # use ral::register;
+//! #
+//! # register! {
+//! ctrl {
+//!     runtime_base: super::BAR0,
+//!     offset: 0x08,
+//!     // ...
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0
+//! }
+//! # }
+//! # }
+//!
+//! # fn main() {
+//! # let mapped_address = 0x4003_0000;
+//! BAR0.set(mapped_address); // Must be set before registers are borrowed, borrowing fails otherwise
+//! # }
+//! ```
+//! Runtime base isn't supported for bus-backed registers
 //!
 //! # Requirenments to custom types
 //!
 //! For read access you must implement `TryFrom<u32> for CustomType` or `From<u32> for CustomType` if your register is 32-bit
 //! For write access you have to implement `TryFrom<CustomType> for u32` or `From<CustomType> for u32` respectively
 //!
-//! # How to use resulting library
+//! # Signed fields
+//!
+//! Fields of `i8`, `i16`, `i32` and `i64` types hold two's complement values, field width must not exceed type size
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # reg0 {
+//! #     offset: 0x0,
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #[doc = "Bits 4:8 - Temperature offset"]
+//! offset[4:5] as i8,
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Getter extends field sign bit, e.g. `0b11111` is read as `-1`, setter returns `Err(value)` without
+//! modifying the register if value doesn't fit in field width, e.g. `set_offset(16)` or `set_offset(-17)`
+//!
+//! # Fixed-point and scaled fields
+//!
+//! Fields holding Qm.n numbers can be declared of [`Fixed`] type with raw integer type and number of fractional bits,
+//! fields with linear scale and offset can be declared of `f32` or `f64` type
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # reg0 {
+//! #     offset: 0x0,
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #[doc = "Bits 0:11 - Gain, Q3.8"]
+//! gain[0:12] as Fixed<i16, 8>,
+//! #[doc = "Bits 16:23 - Temperature, 0.5 degree per LSB starting from -40 degrees"]
+//! temp[16:8] as f32 { scale: 0.5, offset: -40, signed: false }, // All keys are optional, `value = raw * scale + offset`
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Getters return converted values, e.g. `get_gain().to_f32()` and `get_temp()`, setters return `Err(value)`
+//! without modifying the register if value doesn't fit in field width, scaled values are rounded to nearest raw value.
+//! Scaled fields also have `<FIELD>_SCALE` [`Scale`] constant. Raw values of scaled fields are handled as `i64`,
+//! so unsigned scaled fields can be at most 63 bits wide.
+//! Only `Fixed` and `ral::Fixed` paths are treated as fixed-point type, other types named `Fixed` are custom ones
+//!
+//! # Inline enums
+//!
+//! Instead of custom type, field can declare enum inline, `Mode` enum with `TryFrom<u32>` and
+//! `From<Mode> for u32` conversions is then generated next to the register
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # reg0 {
+//! #     offset: 0x0,
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #[doc = "Bits 8:9 - Mode"]
+//! mode[8:2] as enum Mode {
+//!     #[doc = "Low power mode"] // Variants can have descriptions
+//!     Slow = 0,
+//!     Fast = 1,
+//!     Auto = 2
+//! },
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Setter of such field is infallible, getter returns `Result<Mode, u32>` with raw field value as error,
+//! unless enum covers every value of the field width, then it returns `Mode`.
+//! Each variant also has `<FIELD>_<VARIANT>` [field value](#field-values) constant, e.g. `MODE_FAST`
+//!
+//! # Register arrays
+//!
+//! Evenly spaced registers of the same layout can be declared as single register with `count` and `stride` keys
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod ch {
+//! # use ral::register;
+//! #
+//! # register! {
+//! ch {
+//!     offset: 0x20,
+//!     value_size: 32,
+//!     count: 4, // Number of registers
+//!     stride: 0x8, // Distance between consecutive registers in bytes
+//!     reset_mask: 0xFFFFFFFF,
+//!     reset_value: 0x0,
+//!     fields: {
+//!         duty[0:16] as u16,
+//!     }
+//! }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Single `Ch` type is then generated, each register of array is borrowed separately by its index
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod ch {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # ch {
+//! #     offset: 0x20,
+//! #     value_size: 32,
+//! #     count: 4, // Number of registers
+//! #     stride: 0x8, // Distance between consecutive registers in bytes
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #         duty[0:16] as u16,
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {
+//! # use ch::{ch, ch_at, Ch};
+//! let ch1 = ch(1).unwrap(); // `None` if index is out of range or register is already borrowed
+//! let ch3 = ch_at::<3>().unwrap(); // Index is checked at compile time
+//! assert_eq!(ch1.index(), 1);
+//! assert!(ch(1).is_none());
+//! println!("{:?}", Ch::who_holds(1));
+//! # }
+//! ```
+//! `Ch::COUNT` and `Ch::STRIDE` constants describe the array, `try_ch(index)` reports who holds the register
+//! like [`try_borrow_register`] does, or fails with [`BorrowErrorKind::OutOfBounds`] if index is out of range
+//!
+//! # Field arrays
+//!
+//! Evenly spaced fields of the same type can be declared as single field with count and step in bits
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # reg0 {
+//! #     offset: 0x0,
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #[doc = "Pin mode"]
+//! mode[0:2; 16 step 2] as u8, // 16 fields `mode0` to `mode15`, each 2 bits wide and 2 bits apart
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Besides accessors of named fields, e.g. `get_mode3()` and `set_mode3(value)`, indexed ones are generated,
+//! e.g. `get_mode(3)` and `set_mode(3, value)`, panicking if index is out of range.
+//! `MODE_COUNT` and `MODE_STEP` constants describe the array
+//!
+//! # Modified write values
+//!
+//! Fields can be marked with `#[modified_write_values = "<value>"]` attribute, where value is one of
+//! `oneToClear`, `oneToSet`, `oneToToggle`, `zeroToClear`, `zeroToSet`, `zeroToToggle`, `clear`, `set` or `modify`
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod reg0 {
+//! # use ral::register;
+//! #
+//! # register! {
+//! # reg0 {
+//! #     offset: 0x0,
+//! #     value_size: 32,
+//! #     reset_mask: 0xFFFFFFFF,
+//! #     reset_value: 0x0,
+//! #     fields: {
+//! #[doc = "Bit 0 - Update interrupt flag"]
+//! #[modified_write_values = "oneToClear"]
+//! uif[0:1] as bool,
+//! #     }
+//! # }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Bits of `oneTo*` fields are written as `0` and bits of `zeroTo*` fields are written as `1`,
+//! unless they were explicitly modified with setters since last `read()` or `write()`,
+//! so read-modify-write sequence doesn't clear pending flags unintentionally.
+//! For `oneToClear` and `zeroToClear` fields additional `clear_<field>()` method is generated
+//! ```no_run
+//! # use ral::{ReadableRegister, WritableRegister};
+//! #
+//! # ral::peripheral! {
+//! #     tim2 {
+//! #         base_address: 0x4000_0000,
+//! #         cr1 {
+//! #             offset: 0x0,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 cms[5:2] as u8,
+//! #                 dir[4:1] as bool,
+//! #                 cen[0:1] as bool,
+//! #             }
+//! #         },
+//! #         sr {
+//! #             offset: 0x10,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 #[modified_write_values = "oneToClear"]
+//! #                 uif[0:1] as bool,
+//! #             }
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # fn main() {
+//! # use tim2::Cr1;
+//! tim2::sr().unwrap().read().clear_uif().write(); // Clears only `uif` flag
+//! # }
+//! ```
+//!
+//! # Split access
 //!
+//! Registers wider than bus can be accessed in several parts, e.g. 64-bit register on 32-bit bus
+//! ```no_run
+//! # pub const BASE_ADDRESS: usize = 0x4000_0000;
+//! #
+//! # pub mod counter {
+//! # use ral::register;
+//! #
+//! # register! {
+//! counter {
+//!     offset: 0x10,
+//!     value_size: 64,
+//!     access_width: 32, // Optional bus access width, must not exceed value size
+//!     access_order: "high-then-low", // Optional order of partial accesses, `low-then-high` by default
+//!     reset_mask: 0xFFFFFFFFFFFFFFFF,
+//!     reset_value: 0x0
+//! }
+//! # }
+//! # }
+//! #
+//! # fn main() {}
+//! ```
+//! Each `read()` and `write()` of the register above is performed as two 32-bit accesses,
+//! most significant word first
+//!
+//! # Byte order
+//!
+//! Registers of peripherals having byte order different from the core's one can be marked with
+//! `#[byte_order = "big-endian"]` or `#[byte_order = "little-endian"]` attribute,
+//! value is converted on each `read()` and `write()`. Default `native` byte order means no conversion
+//!
+//! # Reserved bits
+//!
+//! Only bits of fields allowing write (or all bits if register has no fields) are written from cache,
+//! the rest of the bits is written according to `#[reserved_bits = "<policy>"]` register attribute:
+//! - `reset-value` - bits are written as in reset value, default policy
+//! - `preserve` - bits are written as they were read
+//! - `zero` - bits are written as zeros
+//!
+//! # Bus-backed registers
+//!
+//! Registers of external devices, e.g. chips connected via I2C or SPI, can be described with
+//! `#[interface = "bus"]` register attribute. Such registers are not exclusively borrowed,
+//! but created on demand, and accessed through user supplied [`Transport`] with fallible `read()` and `write()`
+//! ```no_run
+//! # use ral::{ReadableBusRegister, Transport, WritableBusRegister};
+//! #
+//! # pub mod i2c {
+//! #     pub struct I2c;
+//! #
+//! #     #[derive(Debug)]
+//! #     pub struct Error;
+//! # }
+//! #
+//! # ral::peripheral! {
+//! #     pmic {
+//! #         base_address: 0x0,
+//! #         cluster regulators {
+//! #             offset: 0x10,
+//! #             #[interface = "bus"]
+//! #             ldo1 {
+//! #                 offset: 0x2,
+//! #                 value_size: 8,
+//! #                 reset_mask: 0xFF,
+//! #                 reset_value: 0x0,
+//! #                 fields: {
+//! #                     voltage[0:5] as u8,
+//! #                 }
+//! #             }
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # pub struct Pmic(i2c::I2c);
+//! #
+//! # impl Pmic {
+//! #     fn new(i2c: i2c::I2c) -> Self {
+//! #         Pmic(i2c)
+//! #     }
+//! # }
+//! #
+//! impl Transport for Pmic {
+//!     type Error = i2c::Error;
+//!
+//!     fn read(&mut self, address: usize, width: u32) -> Result<u64, Self::Error> {
+//!         /* ... */
+//! #         unimplemented!()
+//!     }
+//!
+//!     fn write(&mut self, address: usize, width: u32, value: u64) -> Result<(), Self::Error> {
+//!         /* ... */
+//! #         unimplemented!()
+//!     }
+//! }
+//!
+//! # fn main() -> Result<(), i2c::Error> {
+//! # let i2c = i2c::I2c;
+//! let mut pmic = Pmic::new(i2c);
+//! pmic::regulators::ldo1()
+//!     .read(&mut pmic)?
+//!     .set_voltage(0x1F)
+//!     .write(&mut pmic)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Borrow diagnostics
+//!
+//! With `borrow-tracking` feature enabled, location of the successful register borrow is recorded,
+//! so it can be found out who holds the register when `reg0()` returns `None`.
+//! The location is available with `Reg0::who_holds()` or from [`BorrowError`] returned by `try_reg0()`
+//! ```no_run
+//! # ral::peripheral! {
+//! #     peripheral {
+//! #         base_address: 0x4000_0000,
+//! #         reg0 {
+//! #             offset: 0x0,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # fn main() {
+//! let reg0 = peripheral::try_reg0().unwrap_or_else(|error| panic!("{}", error));
+//! // panicked at 'Register is already borrowed at src/driver.rs:42:23'
+//! # }
+//! ```
+//!
+//! # Field values
+//!
+//! Each field has `<FIELD>` constant of [`Field`] type producing [`FieldValue`] with `val(value)`,
+//! `bool` fields additionally have `<FIELD>_SET` and `<FIELD>_CLEAR` constants,
+//! fields of [inline enum](#inline-enums) types have constant for each variant.
+//! Field values of the same register can be combined with `+` or `|` and applied or checked at once
+//! ```no_run
+//! # use ral::{ReadableRegister, WritableRegister};
+//! #
+//! # ral::peripheral! {
+//! #     tim2 {
+//! #         base_address: 0x4000_0000,
+//! #         cr1 {
+//! #             offset: 0x0,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 cms[5:2] as u8,
+//! #                 dir[4:1] as bool,
+//! #                 cen[0:1] as bool,
+//! #             }
+//! #         },
+//! #         sr {
+//! #             offset: 0x10,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 #[modified_write_values = "oneToClear"]
+//! #                 uif[0:1] as bool,
+//! #             }
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # fn main() {
+//! # use tim2::Cr1;
+//! let mut cr1 = tim2::cr1().unwrap();
+//! cr1.read().modify(Cr1::CMS.val(0b01) + Cr1::DIR_CLEAR + Cr1::CEN_SET).write();
+//! assert!(cr1.read().matches_all(Cr1::CMS.val(0b01) + Cr1::CEN_SET));
+//! # }
+//! ```
+//!
+//! # Building values
+//!
+//! Fully specified values can be built from scratch, starting from reset value with `build()` or from zero
+//! with `build_zeroed()`, using the same typed setters, and written with `write_value()`.
+//! Neither the register nor its cached value is read, the cache is replaced by the written value
+//! ```no_run
+//! # use ral::{ReadableRegister, WritableRegister};
+//! #
+//! # ral::peripheral! {
+//! #     tim2 {
+//! #         base_address: 0x4000_0000,
+//! #         cr1 {
+//! #             offset: 0x0,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 cms[5:2] as u8,
+//! #                 dir[4:1] as bool,
+//! #                 cen[0:1] as bool,
+//! #             }
+//! #         },
+//! #         sr {
+//! #             offset: 0x10,
+//! #             value_size: 32,
+//! #             reset_mask: 0xFFFFFFFF,
+//! #             reset_value: 0x0,
+//! #             fields: {
+//! #                 #[modified_write_values = "oneToClear"]
+//! #                 uif[0:1] as bool,
+//! #             }
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # fn main() {
+//! # use tim2::Cr1;
+//! let mut cr1 = tim2::cr1().unwrap();
+//! let mut value = Cr1::build();
+//! value.set_cms(0b01).set_cen();
+//! cr1.write_value(value);
+//! # }
+//! ```
+//! Values are `Copy`, e.g. `cr1.write_value(*Cr1::build().set_cen())`. Only bits set while building are
+//! considered modified, so bits of `oneToClear` like fields are written as no-op unless set explicitly.
+//! Builders aren't generated for read-only and bus-backed registers
+//!
+//! # Sharing registers with interrupt handlers
+//!
+//! Borrowed register can be moved into [`Shared`] wrapper, which can be declared as `static`
+//! and accessed from both thread mode and interrupt handlers with `lock(|r| ...)` under critical section.
+//! The register remains borrowed, i.e. removed from its holder, until it's released from the wrapper and dropped
+//!
+//! # Metadata
+//!
+//! Each register implements [`Introspect`] trait providing static [`RegisterMetadata`] table
+//! with register name, address, size, reset value and mask, access and fields description.
+//! Generated peripheral and cluster modules additionally contain `METADATA` constant aggregating
+//! tables of their registers and clusters, and device crate root contains one for all peripherals
 //! ```
+//! # pub mod device_crate {
+//! #     ral::peripheral! {
+//! #         tim2 {
+//! #             base_address: 0x4000_0000,
+//! #             cr1 {
+//! #                 offset: 0x0,
+//! #                 value_size: 32,
+//! #                 reset_mask: 0xFFFFFFFF,
+//! #                 reset_value: 0x0,
+//! #             }
+//! #         }
+//! #     }
+//! #
+//! #     pub const METADATA: ral::DeviceMetadata = ral::DeviceMetadata {
+//! #         name: "device",
+//! #         description: None,
+//! #         peripherals: &[tim2::METADATA],
+//! #     };
+//! # }
+//! #
+//! # fn main() {
+//! for peripheral in device_crate::METADATA.peripherals {
+//!     for register in peripheral.registers {
+//!         println!("{}.{} @ {:#010X}", peripheral.name, register.name, register.address);
+//!     }
+//! }
+//! # }
+//! ```
+//!
+//! # Simulation
+//!
+//! With `simulation` feature enabled (requires `std`), register accesses can be served by host-side model
+//! instead of hardware, so driver logic can be tested without target device.
+//! Model is active for the thread while [`Simulation`] instance is alive,
+//! read and write callbacks can be attached to register addresses to script peripheral behaviour
+//! ```ignore
+//! let simulation = Simulation::start();
+//! let mut fifo = VecDeque::from(vec![0x12, 0x34]);
+//! simulation.on_read(usart1::BASE_ADDRESS + 0x04, move |_| fifo.pop_front().unwrap_or(0)); // Draining FIFO
+//! assert_eq!(driver::receive(), [0x12, 0x34]);
+//! ```
+//! [`SimulatedTransport`] serves [bus-backed registers](#bus-backed-registers) the same way
+//!
+//! # How to use resulting library
+//!
+//! ```no_run
+//! # pub mod device_crate {
+//! #     pub mod types {
+//! #         #[derive(Clone, Copy, Debug, PartialEq)]
+//! #         pub enum CustomType {
+//! #             One = 1,
+//! #             Two = 2,
+//! #             Three = 3,
+//! #         }
+//! #
+//! #         impl core::convert::TryFrom<u32> for CustomType {
+//! #             type Error = u32;
+//! #
+//! #             fn try_from(value: u32) -> Result<Self, Self::Error> {
+//! #                 match value {
+//! #                     1 => Ok(CustomType::One),
+//! #                     2 => Ok(CustomType::Two),
+//! #                     3 => Ok(CustomType::Three),
+//! #                     _ => Err(value),
+//! #                 }
+//! #             }
+//! #         }
+//! #
+//! #         impl From<CustomType> for u32 {
+//! #             fn from(value: CustomType) -> Self {
+//! #                 value as u32
+//! #             }
+//! #         }
+//! #     }
+//! #
+//! #     pub mod peripherals {
+//! #         ral::peripheral! {
+//! #             use crate::device_crate::types::CustomType;
+//! #
+//! #             peripheral {
+//! #                 base_address: 0x4000_0000,
+//! #                 reg0 {
+//! #                     offset: 0x8,
+//! #                     value_size: 32,
+//! #                     reset_mask: 0xFFFFFFFF,
+//! #                     reset_value: 0x0,
+//! #                     fields: {
+//! #                         #[access = "read-only"]
+//! #                         field5[16:16] as u16,
+//! #                         #[access = "write-only"]
+//! #                         field4[14:2] as u8,
+//! #                         #[access = "read-only"]
+//! #                         field3[11:3] as u8,
+//! #                         field2[10:1] as bool,
+//! #                         field1[8:2] as CustomType,
+//! #                         field0[0:8] as u8
+//! #                     }
+//! #                 }
+//! #             }
+//! #         }
+//! #     }
+//! # }
+//! #
+//! # use device_crate::types::CustomType::{self, One, Three, Two};
+//! # use ral::{ReadableRegister, WritableRegister};
+//! #
+//! # fn main() {
 //! use device_crate::peripherals::peripheral;
 //!
-//! let mut reg0 = peripheral::reg0()
-//!         .unwrap() // Borrow the register
-//!         .read(); // Load current data stored in register
+//! let mut reg0 = peripheral::reg0().unwrap(); // Borrow the register
+//! reg0.read(); // Load current data stored in register
 //! let field3 = reg0.get_field3(); // Read `u8` field
 //!
 //! let field2 = reg0.is_field2_set(); // Read `bool` field
@@ -284,19 +1065,46 @@
 //!     .set_field4(field3 + 10) // Set 'u8' field
 //!     .set_field1(if field1 == Two { One } else { Three }).unwrap() // Set `CustomType` field
 //!     .write(); // Finally write result to hardware register
+//! # }
 //! ```
 #![no_std]
 
+#[cfg(feature = "simulation")]
+extern crate std;
+
 use core::marker::{Copy, PhantomData};
 use core::ops::{BitAnd, BitOr, Not};
-use core::ptr::{null_mut, NonNull};
-use core::sync::atomic::AtomicPtr;
-use core::sync::atomic::Ordering::SeqCst;
+use core::panic::Location;
+use core::ptr::NonNull;
 
 pub use vcell::VolatileCell;
 
 pub use ral_macro::*;
 
+pub use access::{AccessOrder, ByteOrder, ReservedBits};
+pub use bus::*;
+pub use field_value::*;
+pub use holder::*;
+pub use metadata::*;
+pub use numeric::*;
+pub use shared::*;
+#[cfg(feature = "simulation")]
+pub use simulation::*;
+pub use transaction::*;
+
+use access::{load, prepare_write, store};
+
+mod access;
+mod bus;
+mod field_value;
+mod holder;
+mod metadata;
+mod numeric;
+mod shared;
+#[cfg(feature = "simulation")]
+mod simulation;
+mod transaction;
+
 /// Register data holder, abstracts interaction with actual hardware
 pub struct R<ValueType, RegisterType>
 where
@@ -307,6 +1115,20 @@ where
     register_type: PhantomData<RegisterType>,
     register: NonNull<VolatileCell<ValueType>>,
     bits: ValueType,
+    modified: ValueType,
+}
+
+// Pointer is obtained only by borrowing the register from its holder, which hands it out to a single
+// owner at a time, so moving the owner to another thread or interrupt handler can't create aliases
+unsafe impl<ValueType, RegisterType> Send for R<ValueType, RegisterType>
+where
+    ValueType: Send
+        + Copy
+        + Not<Output = ValueType>
+        + BitAnd<Output = ValueType>
+        + BitOr<Output = ValueType>,
+    RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
+{
 }
 
 impl<ValueType, RegisterType> R<ValueType, RegisterType>
@@ -322,49 +1144,147 @@ where
             register_type: PhantomData,
             register: ptr,
             bits: RegisterType::RESET_VALUE,
+            modified: RegisterType::RESET_VALUE & !RegisterType::RESET_VALUE,
         })
     }
 
+    /// Gets pointer to the register
+    #[inline]
+    pub fn as_ptr(&self) -> *mut VolatileCell<ValueType> {
+        self.register.as_ptr()
+    }
+
     /// Gets cached bits
     #[inline]
     pub fn get_bits(&self) -> ValueType {
         self.bits
     }
 
-    /// Stores bits into cache
+    /// Stores bits into cache, all bits are considered explicitly modified
     #[inline]
     pub fn set_bits(&mut self, bits: ValueType) {
         self.bits = bits;
+        self.modified = bits | !bits;
+    }
+
+    /// Stores bits selected by mask into cache, selected bits are considered explicitly modified
+    #[inline]
+    pub fn update_bits(&mut self, mask: ValueType, bits: ValueType) {
+        self.bits = (self.bits & !mask) | (bits & mask);
+        self.modified = self.modified | mask;
     }
 
     /// Loads data from register to cache
     #[inline]
     pub fn read(&mut self) {
         unsafe {
-            self.bits = self.register.as_ref().get();
+            self.bits = load::<ValueType, RegisterType>(self.register.as_ptr());
         }
+        self.modified = self.modified & !self.modified;
     }
 
     /// Stores data from cache to register
+    ///
+    /// Bits outside of [`Register::WRITABLE_MASK`] are written according to
+    /// [`Register::RESERVED_BITS`] policy.
+    /// Bits having side effect on write (see [`Register::ONE_TO_MODIFY_MASK`] and
+    /// [`Register::ZERO_TO_MODIFY_MASK`]) are written as no-op values unless explicitly modified
+    /// since last read or write
     #[inline]
     pub fn write(&mut self) {
+        let bits = prepare_write::<ValueType, RegisterType>(self.bits, self.modified);
         unsafe {
-            self.register.as_ref().set(
-                (!RegisterType::RESET_MASK & RegisterType::RESET_VALUE)
-                    | (RegisterType::RESET_MASK & self.bits),
-            );
+            store::<ValueType, RegisterType>(self.register.as_ptr(), bits);
+        }
+        self.modified = self.modified & !self.modified;
+    }
+
+    /// Replaces the cache with value built from scratch and stores it to register,
+    /// only bits explicitly set while building are considered modified
+    #[inline]
+    pub fn write_value(&mut self, value: RegisterValue<ValueType, RegisterType>) {
+        self.bits = value.bits;
+        self.modified = value.modified;
+        self.write();
+    }
+
+    /// Takes snapshot of the cache
+    #[inline]
+    pub fn save_cache(&self) -> Cache<ValueType> {
+        Cache {
+            bits: self.bits,
+            modified: self.modified,
         }
     }
+
+    /// Restores the cache from snapshot
+    #[inline]
+    pub fn restore_cache(&mut self, cache: Cache<ValueType>) {
+        self.bits = cache.bits;
+        self.modified = cache.modified;
+    }
+}
+
+/// Snapshot of register data holder cache
+#[derive(Clone, Copy)]
+pub struct Cache<ValueType: Copy> {
+    bits: ValueType,
+    modified: ValueType,
+}
+
+/// Register value built from scratch without reading the register, see [`R::write_value`]
+pub struct RegisterValue<ValueType, RegisterType> {
+    register_type: PhantomData<RegisterType>,
+    bits: ValueType,
+    modified: ValueType,
 }
 
-impl<ValueType, RegisterType> Into<*mut VolatileCell<ValueType>> for &mut R<ValueType, RegisterType>
+impl<ValueType, RegisterType> RegisterValue<ValueType, RegisterType>
 where
     ValueType:
         Copy + Not<Output = ValueType> + BitAnd<Output = ValueType> + BitOr<Output = ValueType>,
     RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
 {
-    fn into(self) -> *mut VolatileCell<ValueType> {
-        self.register.as_ptr()
+    /// Creates value starting from given bits, none of them are considered explicitly modified
+    #[inline]
+    pub fn new(bits: ValueType) -> Self {
+        Self {
+            register_type: PhantomData,
+            bits,
+            modified: bits & !bits,
+        }
+    }
+
+    /// Gets bits of the value
+    #[inline]
+    pub fn get_bits(&self) -> ValueType {
+        self.bits
+    }
+
+    /// Stores bits selected by mask, selected bits are considered explicitly modified
+    #[inline]
+    pub fn update_bits(&mut self, mask: ValueType, bits: ValueType) {
+        self.bits = (self.bits & !mask) | (bits & mask);
+        self.modified = self.modified | mask;
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Clone for RegisterValue<ValueType, RegisterType> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<ValueType: Copy, RegisterType> Copy for RegisterValue<ValueType, RegisterType> {}
+
+impl<ValueType, RegisterType> From<&mut R<ValueType, RegisterType>> for *mut VolatileCell<ValueType>
+where
+    ValueType:
+        Copy + Not<Output = ValueType> + BitAnd<Output = ValueType> + BitOr<Output = ValueType>,
+    RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
+{
+    fn from(val: &mut R<ValueType, RegisterType>) -> Self {
+        val.register.as_ptr()
     }
 }
 
@@ -377,15 +1297,65 @@ pub trait Register {
     type ValueType: Copy
         + Not<Output = Self::ValueType>
         + BitAnd<Output = Self::ValueType>
-        + BitOr<Output = Self::ValueType>;
+        + BitOr<Output = Self::ValueType>
+        + RegisterBits;
+
+    /// Absolute address of the register, or its address on the bus for bus-backed registers
+    const ADDRESS: usize;
 
     /// Reset mask
     const RESET_MASK: Self::ValueType;
 
     /// Reset value
     const RESET_VALUE: Self::ValueType;
+
+    /// Bits of writable fields, or all bits if register has no fields
+    const WRITABLE_MASK: Self::ValueType = Self::ValueType::ONES;
+
+    /// How bits outside of writable mask are written
+    const RESERVED_BITS: ReservedBits = ReservedBits::ResetValue;
+
+    /// Bus access width in bits, registers wider than that are accessed in several parts
+    const ACCESS_WIDTH: u32 = 64;
+
+    /// Order of partial accesses, only matters if register is wider than access width
+    const ACCESS_ORDER: AccessOrder = AccessOrder::LowThenHigh;
+
+    /// Byte order of the register value
+    const BYTE_ORDER: ByteOrder = ByteOrder::Native;
+
+    /// Bits modified by writing ones (`oneToClear`, `oneToSet`, `oneToToggle`),
+    /// written as zeros unless explicitly modified
+    const ONE_TO_MODIFY_MASK: Self::ValueType = Self::ValueType::ZERO;
+
+    /// Bits modified by writing zeros (`zeroToClear`, `zeroToSet`, `zeroToToggle`),
+    /// written as ones unless explicitly modified
+    const ZERO_TO_MODIFY_MASK: Self::ValueType = Self::ValueType::ZERO;
+}
+
+/// Constant bit patterns of register value types, used for default masks of [`Register`]
+pub trait RegisterBits {
+    /// All bits cleared
+    const ZERO: Self;
+
+    /// All bits set
+    const ONES: Self;
+}
+
+macro_rules! register_bits {
+    ($($t: ty),*) => {
+        $(
+            impl RegisterBits for $t {
+                const ZERO: Self = 0;
+
+                const ONES: Self = <$t>::MAX;
+            }
+        )*
+    };
 }
 
+register_bits!(u8, u16, u32, u64);
+
 /// Trait representing readable part of register, actual registers are to implement this
 pub trait ReadableRegister: Register {
     /// Get value of the register as raw bits
@@ -405,6 +1375,21 @@ pub trait WritableRegister: Register {
 
     /// Writes value to the register
     fn write(&mut self) -> &mut Self::RegisterType;
+
+    /// Takes snapshot of the cached value
+    fn save_cache(&self) -> Cache<Self::ValueType>;
+
+    /// Restores cached value from snapshot
+    fn restore_cache(&mut self, cache: Cache<Self::ValueType>) -> &mut Self::RegisterType;
+
+    /// Set value of the register as raw bits saved earlier, unlike [`set_bits`](Self::set_bits)
+    /// bits having side effect on write aren't considered modified, so they are written as no-op values
+    fn restore_bits(&mut self, bits: Self::ValueType) -> &mut Self::RegisterType {
+        self.restore_cache(Cache {
+            bits,
+            modified: !(Self::ONE_TO_MODIFY_MASK | Self::ZERO_TO_MODIFY_MASK),
+        })
+    }
 }
 
 /// Extract specific bits from register value
@@ -419,7 +1404,7 @@ macro_rules! value_read {
 #[macro_export]
 macro_rules! value_write {
     ($r: expr, $m: expr, $o: expr, $v: expr) => {
-        $r.0.set_bits(($r.0.get_bits() & !($m << $o)) | ((($v) & $m) << $o));
+        $r.0.update_bits($m << $o, (($v) & $m) << $o);
     };
 }
 
@@ -427,32 +1412,48 @@ macro_rules! value_write {
 #[macro_export]
 macro_rules! init_register {
     ($a: expr, $t: tt) => {
-        AtomicPtr::new($a as *mut VolatileCell<<$t as Register>::ValueType>)
+        $crate::RegisterHolder::new(
+            $a as *mut $crate::VolatileCell<<$t as $crate::Register>::ValueType>,
+        )
+    };
+    ($a: expr, $t: tt, $b: expr) => {
+        $crate::RegisterHolder::<<$t as $crate::Register>::ValueType>::relative($a, &$b)
     };
 }
 
-/// Exclusively borrows register
+/// Exclusively borrows register, returns `None` if it's already borrowed
+#[track_caller]
 pub fn borrow_register<ValueType, RegisterType>(
-    holder: &AtomicPtr<VolatileCell<ValueType>>,
+    holder: &RegisterHolder<ValueType>,
 ) -> Option<R<ValueType, RegisterType>>
 where
     ValueType:
         Copy + Not<Output = ValueType> + BitAnd<Output = ValueType> + BitOr<Output = ValueType>,
     RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
 {
-    R::new(holder.swap(null_mut(), SeqCst))
+    try_borrow_register(holder).ok()
 }
 
-/// Releases register, so it can be borrowed again
-pub fn return_register<ValueType, RegisterType>(
-    holder: &AtomicPtr<VolatileCell<ValueType>>,
-    register: &mut R<ValueType, RegisterType>,
-) where
+/// Exclusively borrows register, error reports who holds the register if it's already borrowed
+#[track_caller]
+pub fn try_borrow_register<ValueType, RegisterType>(
+    holder: &RegisterHolder<ValueType>,
+) -> Result<R<ValueType, RegisterType>, BorrowError>
+where
     ValueType:
         Copy + Not<Output = ValueType> + BitAnd<Output = ValueType> + BitOr<Output = ValueType>,
     RegisterType: Register<RegisterType = RegisterType, ValueType = ValueType>,
 {
-    holder.swap(register.into(), SeqCst);
+    holder
+        .take(Location::caller())
+        .map(|register| R::new(register).unwrap())
+}
+
+/// Releases register, so it can be borrowed again
+///
+/// Intended to be called only by the owner of the register borrowed from `holder`, e.g. in its `Drop`
+pub fn return_register<ValueType>(holder: &RegisterHolder<ValueType>) {
+    holder.put();
 }
 
 #[cfg(test)]
@@ -466,9 +1467,17 @@ mod tests {
 
         type ValueType = u32;
 
+        const ADDRESS: usize = 0x0000_0000;
+
         const RESET_MASK: Self::ValueType = 0xF3FF_FFFF;
 
         const RESET_VALUE: Self::ValueType = 0x2800_0000;
+
+        const WRITABLE_MASK: Self::ValueType = 0xF3FF_FFFF;
+
+        const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
+
+        const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
     }
 
     impl ReadableRegister for TestR {
@@ -496,9 +1505,35 @@ mod tests {
             self.0.write();
             self
         }
+
+        fn save_cache(&self) -> Cache<Self::ValueType> {
+            self.0.save_cache()
+        }
+
+        fn restore_cache(&mut self, cache: Cache<Self::ValueType>) -> &mut Self::RegisterType {
+            self.0.restore_cache(cache);
+            self
+        }
     }
 
     impl TestR {
+        pub const TEST_30_31: Field<u32, Self> = Field::new(0x3u32, 30);
+
+        pub const TEST_0_3: Field<u32, Self> = Field::new(0xFu32, 0);
+
+        pub fn modify(&mut self, value: FieldValue<u32, Self>) -> &mut Self {
+            self.0.update_bits(value.mask(), value.value());
+            self
+        }
+
+        pub fn matches_all(&self, value: FieldValue<u32, Self>) -> bool {
+            value.matches_all(self.0.get_bits())
+        }
+
+        pub fn matches_any(&self, value: FieldValue<u32, Self>) -> bool {
+            value.matches_any(self.0.get_bits())
+        }
+
         #[inline]
         pub fn get_test_30_31(&self) -> u8 {
             value_read!(self, 0x3u32, 30) as u8
@@ -518,29 +1553,66 @@ mod tests {
             value_write!(self, 0xFu32, 0, value as <Self as Register>::ValueType);
             self
         }
+
+        pub fn set_test_4_7(&mut self, value: u8) -> Result<&mut Self, u8> {
+            if value > 0xF {
+                return Err(value);
+            }
+            value_write!(self, 0xFu32, 4, value as <Self as Register>::ValueType);
+            Ok(self)
+        }
     }
 
     #[test]
     fn test_register_access() {
         let mut registry_data: u32 = 0x89AB_CDEF;
 
-        let register_holder: AtomicPtr<VolatileCell<<TestR as Register>::ValueType>> =
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
             init_register!(&mut registry_data as *mut u32, TestR);
-        let mut register_some: Option<R<u32, TestR>> = borrow_register(&register_holder);
+        let register_some: Option<R<u32, TestR>> = borrow_register(&register_holder);
         let register_none: Option<R<u32, TestR>> = borrow_register(&register_holder);
         assert!(register_some.is_some());
         assert!(register_none.is_none());
-        return_register(&register_holder, register_some.as_mut().unwrap());
-        let mut register: Option<R<u32, TestR>> = borrow_register(&register_holder);
+        assert_eq!(
+            register_some.as_ref().unwrap().as_ptr() as *mut u32,
+            &mut registry_data as *mut u32
+        );
+        return_register(&register_holder);
+        let register: Option<R<u32, TestR>> = borrow_register(&register_holder);
         assert!(register.is_some());
-        return_register(&register_holder, register.as_mut().unwrap());
+        return_register(&register_holder);
+    }
+
+    #[test]
+    fn test_try_borrow_register() {
+        let mut registry_data: u32 = 0x89AB_CDEF;
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestR);
+        assert!(register_holder.who_holds().is_none());
+        let _register: R<u32, TestR> = try_borrow_register(&register_holder).unwrap();
+        let line = line!() - 1;
+        let error = try_borrow_register::<u32, TestR>(&register_holder)
+            .err()
+            .unwrap();
+        if cfg!(feature = "borrow-tracking") {
+            let location = register_holder.who_holds().unwrap();
+            assert_eq!((location.file(), location.line()), (file!(), line));
+            assert_eq!(error.who_holds(), Some(location));
+        } else {
+            assert!(register_holder.who_holds().is_none());
+            assert!(error.who_holds().is_none());
+        }
+        return_register(&register_holder);
+        assert!(register_holder.who_holds().is_none());
+        assert!(try_borrow_register::<u32, TestR>(&register_holder).is_ok());
     }
 
     #[test]
     fn test_get() {
         let mut registry_data: u32 = 0x8765_4321;
 
-        let register_holder: AtomicPtr<VolatileCell<<TestR as Register>::ValueType>> =
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
             init_register!(&mut registry_data as *mut u32, TestR);
         let mut register = borrow_register(&register_holder).map(TestR).unwrap();
         register.read();
@@ -552,12 +1624,11 @@ mod tests {
     fn test_set() {
         let mut registry_data: u32 = 0x8765_4321;
 
-        let register_holder: AtomicPtr<VolatileCell<<TestR as Register>::ValueType>> =
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
             init_register!(&mut registry_data as *mut u32, TestR);
         let mut register = borrow_register(&register_holder).map(TestR).unwrap();
         register.set_test_0_3(0xDAu8).set_test_30_31(0x0Au8).write();
-        let TestR(r) = &mut register;
-        return_register(&register_holder, r);
+        return_register(&register_holder);
         assert_eq!(registry_data, 0x8000_000A | TestR::RESET_VALUE);
     }
 
@@ -565,7 +1636,7 @@ mod tests {
     fn test_update() {
         let mut registry_data: u32 = 0x8765_4321;
 
-        let register_holder: AtomicPtr<VolatileCell<<TestR as Register>::ValueType>> =
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
             init_register!(&mut registry_data as *mut u32, TestR);
         let mut register = borrow_register(&register_holder).map(TestR).unwrap();
         register
@@ -573,8 +1644,30 @@ mod tests {
             .set_test_0_3(0xDAu8)
             .set_test_30_31(0x01u8)
             .write();
-        let TestR(r) = &mut register;
-        return_register(&register_holder, r);
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x4B65_432A);
+    }
+
+    #[test]
+    fn test_field_values() {
+        let mut registry_data: u32 = 0x8765_4321;
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestR);
+        let mut register = borrow_register(&register_holder).map(TestR).unwrap();
+        let value = TestR::TEST_0_3.val(0xDA) + TestR::TEST_30_31.val(0x01);
+        assert_eq!(value.mask(), 0xC000_000F);
+        assert_eq!(value.value(), 0x4000_000A);
+        let same = TestR::TEST_30_31.val(0x01) | TestR::TEST_0_3.val(0x0A);
+        assert_eq!((same.mask(), same.value()), (value.mask(), value.value()));
+        assert_eq!(TestR::TEST_30_31.read(0x8765_4321), 0x02);
+        assert!(register.read().matches_all(TestR::TEST_0_3.val(0x01)));
+        assert!(!register.matches_all(value));
+        assert!(!register.matches_any(value));
+        register.modify(value).write();
+        assert!(register.matches_all(value));
+        assert!(register.matches_any(TestR::TEST_0_3.val(0x03)));
+        return_register(&register_holder);
         assert_eq!(registry_data, 0x4B65_432A);
     }
 
@@ -582,14 +1675,565 @@ mod tests {
     fn test_reset() {
         let mut registry_data: u32 = 0x8765_4321;
 
-        let register_holder: AtomicPtr<VolatileCell<<TestR as Register>::ValueType>> =
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
             init_register!(&mut registry_data as *mut u32, TestR);
         let mut register = borrow_register(&register_holder).map(TestR).unwrap();
         register.read();
         register.reset();
         register.write();
-        let TestR(r) = &mut register;
-        return_register(&register_holder, r);
+        return_register(&register_holder);
         assert_eq!(registry_data, TestR::RESET_VALUE);
     }
+
+    struct TestFlagsR(R<u32, TestFlagsR>);
+
+    impl Register for TestFlagsR {
+        type RegisterType = Self;
+
+        type ValueType = u32;
+
+        const ADDRESS: usize = 0x0000_0000;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF_FFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x0000_0000;
+
+        const WRITABLE_MASK: Self::ValueType = 0xFFFF_FFFF;
+
+        const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000_000F;
+
+        const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000_00F0;
+    }
+
+    impl ReadableRegister for TestFlagsR {
+        fn get_bits(&self) -> Self::ValueType {
+            self.0.get_bits()
+        }
+
+        fn read(&mut self) -> &mut Self::RegisterType {
+            self.0.read();
+            self
+        }
+    }
+
+    impl WritableRegister for TestFlagsR {
+        fn set_bits(&mut self, bits: Self::ValueType) -> &mut Self::RegisterType {
+            self.0.set_bits(bits);
+            self
+        }
+
+        fn reset(&mut self) -> &mut Self::RegisterType {
+            self.set_bits(Self::RESET_VALUE)
+        }
+
+        fn write(&mut self) -> &mut Self::RegisterType {
+            self.0.write();
+            self
+        }
+
+        fn save_cache(&self) -> Cache<Self::ValueType> {
+            self.0.save_cache()
+        }
+
+        fn restore_cache(&mut self, cache: Cache<Self::ValueType>) -> &mut Self::RegisterType {
+            self.0.restore_cache(cache);
+            self
+        }
+    }
+
+    impl TestFlagsR {
+        pub fn clear_one_to_clear(&mut self) -> &mut Self {
+            value_write!(self, 0xFu32, 0, 0xFu32);
+            self
+        }
+
+        pub fn clear_zero_to_clear(&mut self) -> &mut Self {
+            value_write!(self, 0xFu32, 4, 0x0u32);
+            self
+        }
+
+        pub fn set_test_8_15(&mut self, value: u8) -> &mut Self {
+            value_write!(self, 0xFFu32, 8, value as <Self as Register>::ValueType);
+            self
+        }
+    }
+
+    #[test]
+    fn test_modified_write_values_untouched() {
+        let mut registry_data: u32 = 0x0000_0F05;
+
+        let register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestFlagsR).unwrap();
+        register.read();
+        register.set_test_8_15(0xA5u8);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x0000_A5F0);
+    }
+
+    #[test]
+    fn test_modified_write_values_explicit() {
+        let mut registry_data: u32 = 0x0000_0F05;
+
+        let register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestFlagsR).unwrap();
+        register.read();
+        register.clear_one_to_clear().clear_zero_to_clear();
+        register.write();
+        assert_eq!(registry_data, 0x0000_0F0F);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x0000_0FF0);
+    }
+
+    #[test]
+    fn test_restore_bits() {
+        let mut registry_data: u32 = 0x0000_0F05;
+
+        let register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestFlagsR).unwrap();
+        let saved = register.read().get_bits();
+        register.set_test_8_15(0xA5u8).write();
+        assert_eq!(registry_data, 0x0000_A5F0);
+        register.set_bits(0x0000_A505).write();
+        register.restore_bits(saved).write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x0000_0FF0);
+    }
+
+    #[test]
+    fn test_write_value() {
+        let mut registry_data: u32 = 0x0000_0F05;
+
+        let register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestFlagsR).unwrap();
+        let mut value = RegisterValue::<u32, TestFlagsR>::new(0x0000_0A00);
+        value.update_bits(0x0000_0001, 0x0000_0001);
+        assert_eq!(value.get_bits(), 0x0000_0A01);
+        register.0.write_value(value);
+        assert_eq!(register.get_bits(), 0x0000_0A01);
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x0000_0AF1);
+    }
+
+    #[test]
+    fn test_transaction_commit() {
+        let mut registry_data: [u32; 2] = [0x8765_4321, 0x0000_0F05];
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut registry_data[0] as *mut u32, TestR);
+        let flags_register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data[1] as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestR).unwrap();
+        let mut flags_register = borrow_register(&flags_register_holder)
+            .map(TestFlagsR)
+            .unwrap();
+        register.read();
+        flags_register.read();
+        Transaction::new()
+            .try_stage(&mut register, |r| r.set_test_4_7(0x5u8))
+            .unwrap()
+            .stage(&mut flags_register, |r| r.clear_one_to_clear())
+            .commit_critical();
+        return_register(&register_holder);
+        return_register(&flags_register_holder);
+        assert_eq!(registry_data, [0x8B65_4351, 0x0000_0FFF]);
+    }
+
+    #[test]
+    fn test_transaction_rollback() {
+        let mut registry_data: [u32; 2] = [0x8765_4321, 0x0000_0F05];
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut registry_data[0] as *mut u32, TestR);
+        let flags_register_holder: RegisterHolder<<TestFlagsR as Register>::ValueType> =
+            init_register!(&mut registry_data[1] as *mut u32, TestFlagsR);
+        let mut register = borrow_register(&register_holder).map(TestR).unwrap();
+        let mut flags_register = borrow_register(&flags_register_holder)
+            .map(TestFlagsR)
+            .unwrap();
+        register.read();
+        flags_register.read();
+        let result = Transaction::new()
+            .stage(&mut flags_register, |r| r.set_test_8_15(0xA5u8))
+            .try_stage(&mut register, |r| {
+                r.set_test_0_3(0x7u8).set_test_4_7(0x10u8)
+            });
+        assert_eq!(result.err(), Some(0x10u8));
+        assert_eq!(register.get_bits(), 0x8765_4321);
+        register.write();
+        flags_register.write();
+        return_register(&register_holder);
+        return_register(&flags_register_holder);
+        assert_eq!(registry_data, [0x8B65_4321, 0x0000_0FF0]);
+    }
+
+    struct TestWideR;
+
+    impl Register for TestWideR {
+        type RegisterType = Self;
+
+        type ValueType = u64;
+
+        const ADDRESS: usize = 0x0000_0000;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF_FFFF_FFFF_FFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x0000_0000_0000_0000;
+
+        const ACCESS_WIDTH: u32 = 16;
+
+        const ACCESS_ORDER: AccessOrder = AccessOrder::HighThenLow;
+    }
+
+    #[test]
+    fn test_split_access() {
+        let mut registry_data: u64 = 0x0123_4567_89AB_CDEF;
+
+        let register_holder: RegisterHolder<<TestWideR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u64, TestWideR);
+        let mut register: R<u64, TestWideR> = borrow_register(&register_holder).unwrap();
+        register.read();
+        assert_eq!(register.get_bits(), 0x0123_4567_89AB_CDEF);
+        register.set_bits(0xFEDC_BA98_7654_3210);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0xFEDC_BA98_7654_3210);
+    }
+
+    struct TestBigEndianR;
+
+    impl Register for TestBigEndianR {
+        type RegisterType = Self;
+
+        type ValueType = u64;
+
+        const ADDRESS: usize = 0x0000_0000;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF_FFFF_FFFF_FFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x0000_0000_0000_0000;
+
+        const ACCESS_WIDTH: u32 = 32;
+
+        const BYTE_ORDER: ByteOrder = ByteOrder::BigEndian;
+    }
+
+    #[test]
+    fn test_byte_order() {
+        let mut registry_data: u64 = 0x0123_4567_89AB_CDEFu64.to_be();
+
+        let register_holder: RegisterHolder<<TestBigEndianR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u64, TestBigEndianR);
+        let mut register: R<u64, TestBigEndianR> = borrow_register(&register_holder).unwrap();
+        register.read();
+        assert_eq!(register.get_bits(), 0x0123_4567_89AB_CDEF);
+        register.set_bits(0xFEDC_BA98_7654_3210);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0xFEDC_BA98_7654_3210u64.to_be());
+    }
+
+    struct TestPreservedR;
+
+    impl Register for TestPreservedR {
+        type RegisterType = Self;
+
+        type ValueType = u32;
+
+        const ADDRESS: usize = 0x0000_0000;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF_FFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x1234_0000;
+
+        const WRITABLE_MASK: Self::ValueType = 0x0000_FFFF;
+
+        const RESERVED_BITS: ReservedBits = ReservedBits::Preserve;
+
+        const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
+
+        const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
+    }
+
+    struct TestZeroedR;
+
+    impl Register for TestZeroedR {
+        type RegisterType = Self;
+
+        type ValueType = u32;
+
+        const ADDRESS: usize = 0x0000_0000;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF_FFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x1234_0000;
+
+        const WRITABLE_MASK: Self::ValueType = 0x0000_FFFF;
+
+        const RESERVED_BITS: ReservedBits = ReservedBits::Zero;
+
+        const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
+
+        const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000_0000;
+    }
+
+    #[test]
+    fn test_reserved_bits() {
+        let mut registry_data: u32 = 0xABCD_0000;
+
+        let register_holder: RegisterHolder<<TestPreservedR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestPreservedR);
+        let mut register: R<u32, TestPreservedR> = borrow_register(&register_holder).unwrap();
+        register.read();
+        register.set_bits(0xFFFF_5678);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0xFFFF_5678);
+
+        let register_holder: RegisterHolder<<TestZeroedR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestZeroedR);
+        let mut register: R<u32, TestZeroedR> = borrow_register(&register_holder).unwrap();
+        register.read();
+        register.set_bits(0xFFFF_1234);
+        register.write();
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x0000_1234);
+    }
+
+    struct TestBusR(BusR<u16, TestBusR>);
+
+    impl Register for TestBusR {
+        type RegisterType = Self;
+
+        type ValueType = u16;
+
+        const ADDRESS: usize = 0x1C;
+
+        const RESET_MASK: Self::ValueType = 0xFFFF;
+
+        const RESET_VALUE: Self::ValueType = 0x0000;
+
+        const WRITABLE_MASK: Self::ValueType = 0x00FF;
+
+        const ONE_TO_MODIFY_MASK: Self::ValueType = 0x0000;
+
+        const ZERO_TO_MODIFY_MASK: Self::ValueType = 0x0000;
+    }
+
+    impl ReadableBusRegister for TestBusR {
+        fn get_bits(&self) -> Self::ValueType {
+            self.0.get_bits()
+        }
+
+        fn read<T: Transport>(
+            &mut self,
+            transport: &mut T,
+        ) -> Result<&mut Self::RegisterType, T::Error> {
+            self.0.read(transport)?;
+            Ok(self)
+        }
+    }
+
+    impl WritableBusRegister for TestBusR {
+        fn set_bits(&mut self, bits: Self::ValueType) -> &mut Self::RegisterType {
+            self.0.set_bits(bits);
+            self
+        }
+
+        fn reset(&mut self) -> &mut Self::RegisterType {
+            self.set_bits(Self::RESET_VALUE)
+        }
+
+        fn write<T: Transport>(
+            &mut self,
+            transport: &mut T,
+        ) -> Result<&mut Self::RegisterType, T::Error> {
+            self.0.write(transport)?;
+            Ok(self)
+        }
+
+        fn save_cache(&self) -> Cache<Self::ValueType> {
+            self.0.save_cache()
+        }
+
+        fn restore_cache(&mut self, cache: Cache<Self::ValueType>) -> &mut Self::RegisterType {
+            self.0.restore_cache(cache);
+            self
+        }
+    }
+
+    struct TestTransport {
+        address: usize,
+        width: u32,
+        value: u64,
+        fail: bool,
+    }
+
+    impl Transport for TestTransport {
+        type Error = ();
+
+        fn read(&mut self, address: usize, width: u32) -> Result<u64, Self::Error> {
+            if self.fail {
+                return Err(());
+            }
+            self.address = address;
+            self.width = width;
+            Ok(self.value)
+        }
+
+        fn write(&mut self, address: usize, width: u32, value: u64) -> Result<(), Self::Error> {
+            if self.fail {
+                return Err(());
+            }
+            self.address = address;
+            self.width = width;
+            self.value = value;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_bus_access() {
+        let mut transport = TestTransport {
+            address: 0,
+            width: 0,
+            value: 0xAB12,
+            fail: false,
+        };
+        let mut register = TestBusR(BusR::new(TestBusR::ADDRESS));
+        register.read(&mut transport).unwrap();
+        assert_eq!((transport.address, transport.width), (0x1C, 16));
+        assert_eq!(register.get_bits(), 0xAB12);
+        register.set_bits(0xFF34).write(&mut transport).unwrap();
+        assert_eq!(transport.value, 0x0034);
+
+        transport.fail = true;
+        assert!(register.read(&mut transport).is_err());
+        assert!(register.write(&mut transport).is_err());
+        assert_eq!(register.get_bits(), 0xFF34);
+    }
+
+    #[cfg(feature = "simulation")]
+    #[test]
+    fn test_simulation() {
+        let mut registry_data: u32 = 0;
+        let address = &mut registry_data as *mut u32 as usize;
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(address, TestR);
+        let mut register = borrow_register(&register_holder).map(TestR).unwrap();
+
+        let simulation = Simulation::start();
+        simulation
+            .poke(address, 0x8765_4321)
+            .on_write(address, move |memory, value| {
+                memory.set(address, value | 0x0000_F000);
+            });
+        register.read();
+        assert_eq!(register.get_test_0_3(), 0x01);
+        register.set_test_0_3(0x05).write();
+        assert_eq!(simulation.peek(address), 0x8B65_F325);
+        assert_eq!(registry_data, 0);
+
+        let mut reads = 0;
+        simulation.on_read(address, move |_| {
+            reads += 1;
+            reads
+        });
+        assert_eq!(register.read().get_bits(), 1);
+        assert_eq!(register.read().get_bits(), 2);
+
+        let mut transport = SimulatedTransport;
+        transport.write(0x1C, 8, 0x1234).unwrap();
+        assert_eq!(simulation.peek(0x1C), 0x34);
+
+        simulation.on_write(0x20, |memory, value| {
+            let status = SimulatedTransport.read(0x1C, 8).unwrap();
+            memory.set(0x20, value | status);
+        });
+        transport.write(0x20, 8, 0x01).unwrap();
+        assert_eq!(simulation.peek(0x20), 0x35);
+
+        drop(simulation);
+        register.read();
+        assert_eq!(register.get_bits(), 0);
+        return_register(&register_holder);
+    }
+
+    #[test]
+    fn test_shared() {
+        let mut registry_data: u32 = 0x8765_4321;
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut registry_data as *mut u32, TestR);
+        let shared: Shared<TestR> = Shared::new();
+        assert_eq!(shared.lock(|r| r.get_bits()), None);
+        shared
+            .share(borrow_register(&register_holder).map(TestR).unwrap())
+            .ok()
+            .unwrap();
+        assert!(borrow_register::<u32, TestR>(&register_holder).is_none());
+        let mut other_data: u32 = 0;
+        let other_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(&mut other_data as *mut u32, TestR);
+        let other = borrow_register(&other_holder).map(TestR).unwrap();
+        assert!(shared.share(other).is_err());
+        return_register(&other_holder);
+        assert_eq!(shared.lock(|r| r.read().get_test_0_3()), Some(0x01));
+        shared.lock(|r| {
+            r.set_test_0_3(0x05).write();
+        });
+        assert!(shared.release().is_some());
+        return_register(&register_holder);
+        assert_eq!(registry_data, 0x8B65_4325);
+        assert!(shared.release().is_none());
+
+        fn assert_sync<T: Sync>() {}
+        assert_sync::<Shared<TestR>>();
+    }
+
+    #[test]
+    fn test_runtime_base() {
+        static BASE: RuntimeBase = RuntimeBase::new();
+        let mut registry_data: [u32; 2] = [0x8765_4321, 0x8765_4321];
+
+        let register_holder: RegisterHolder<<TestR as Register>::ValueType> =
+            init_register!(0x04, TestR, BASE);
+        assert!(BASE.get().is_none());
+        assert!(borrow_register::<u32, TestR>(&register_holder).is_none());
+        let error = try_borrow_register::<u32, TestR>(&register_holder)
+            .err()
+            .unwrap();
+        assert_eq!(error.kind(), BorrowErrorKind::BaseNotSet);
+        BASE.set(registry_data.as_mut_ptr() as usize);
+        let mut register = borrow_register(&register_holder).map(TestR).unwrap();
+        register.read().set_test_0_3(0x0A).write();
+        assert!(borrow_register::<u32, TestR>(&register_holder).is_none());
+        return_register(&register_holder);
+        assert_eq!(registry_data, [0x8765_4321, 0x8B65_432A]);
+    }
+
+    #[test]
+    fn test_fixed_and_scale() {
+        let value = Fixed::<i16, 8>::from_f32(-1.5).unwrap();
+        assert_eq!(value.to_bits(), -384);
+        assert_eq!(value.to_f32(), -1.5);
+        assert_eq!(Fixed::<i8, 4>::from_f64(0.03).map(Fixed::to_bits), Some(0));
+        assert_eq!(Fixed::<i8, 4>::from_f64(0.04).map(Fixed::to_bits), Some(1));
+        assert_eq!(Fixed::<i8, 4>::from_f64(8.0), None);
+        assert_eq!(Fixed::<u8, 4>::from_f64(-0.5), None);
+        assert_eq!(Fixed::<u8, 0>::from_f64(f64::NAN), None);
+
+        let scale = Scale::new(0.5, -40.0);
+        assert_eq!(scale.to_value(130), 25.0);
+        assert_eq!(scale.to_raw(25.2, 0, 255), Some(130));
+        assert_eq!(scale.to_raw(-40.2, 0, 255), Some(0));
+        assert_eq!(scale.to_raw(-41.0, 0, 255), None);
+        assert_eq!(scale.to_raw(87.8, 0, 255), None);
+        assert_eq!(scale.to_raw(f64::INFINITY, 0, 255), None);
+    }
 }
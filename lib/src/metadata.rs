@@ -0,0 +1,101 @@
+/// Access allowed to register or field
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessMode {
+    /// Only reads are allowed
+    ReadOnly,
+    /// Only writes are allowed
+    WriteOnly,
+    /// Both reads and writes are allowed
+    ReadWrite,
+    /// Only first write after reset has effect
+    WriteOnce,
+    /// Reads are allowed, only first write after reset has effect
+    ReadWriteOnce,
+}
+
+/// Description of register field
+#[derive(Clone, Copy, Debug)]
+pub struct FieldMetadata {
+    /// Field name
+    pub name: &'static str,
+    /// Field description
+    pub description: Option<&'static str>,
+    /// Offset of the field's least significant bit
+    pub offset: u32,
+    /// Field width in bits
+    pub width: u32,
+    /// Field access
+    pub access: AccessMode,
+}
+
+/// Description of register
+#[derive(Clone, Copy, Debug)]
+pub struct RegisterMetadata {
+    /// Register name
+    pub name: &'static str,
+    /// Register description
+    pub description: Option<&'static str>,
+    /// Register address, address of the first register for register arrays
+    pub address: usize,
+    /// Number of registers in the array, `1` for single register
+    pub count: usize,
+    /// Distance between registers of the array in bytes, `0` for single register
+    pub stride: usize,
+    /// Register size in bits
+    pub size: u32,
+    /// Reset value
+    pub reset_value: u64,
+    /// Reset mask
+    pub reset_mask: u64,
+    /// Register access
+    pub access: AccessMode,
+    /// Register fields
+    pub fields: &'static [FieldMetadata],
+}
+
+/// Description of cluster of registers
+#[derive(Clone, Copy, Debug)]
+pub struct ClusterMetadata {
+    /// Cluster name
+    pub name: &'static str,
+    /// Cluster description
+    pub description: Option<&'static str>,
+    /// Cluster base address
+    pub address: usize,
+    /// Nested clusters
+    pub clusters: &'static [ClusterMetadata],
+    /// Cluster registers
+    pub registers: &'static [RegisterMetadata],
+}
+
+/// Description of peripheral
+#[derive(Clone, Copy, Debug)]
+pub struct PeripheralMetadata {
+    /// Peripheral name
+    pub name: &'static str,
+    /// Peripheral description
+    pub description: Option<&'static str>,
+    /// Peripheral base address
+    pub address: usize,
+    /// Peripheral clusters
+    pub clusters: &'static [ClusterMetadata],
+    /// Peripheral registers
+    pub registers: &'static [RegisterMetadata],
+}
+
+/// Description of device
+#[derive(Clone, Copy, Debug)]
+pub struct DeviceMetadata {
+    /// Device name
+    pub name: &'static str,
+    /// Device description
+    pub description: Option<&'static str>,
+    /// Device peripherals
+    pub peripherals: &'static [PeripheralMetadata],
+}
+
+/// Trait providing static description of register, actual registers are to implement this
+pub trait Introspect {
+    /// Register description
+    const METADATA: RegisterMetadata;
+}
@@ -0,0 +1,113 @@
+/// Fixed-point number with `FRAC` fractional bits stored as raw bits of `T`, e.g. `Fixed<i16, 12>` is Q3.12
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<T, const FRAC: u32>(T);
+
+impl<T: Copy, const FRAC: u32> Fixed<T, FRAC> {
+    /// Creates fixed-point number from its raw bits
+    #[inline]
+    pub const fn from_bits(bits: T) -> Self {
+        Fixed(bits)
+    }
+
+    /// Gets raw bits of fixed-point number
+    #[inline]
+    pub fn to_bits(self) -> T {
+        self.0
+    }
+}
+
+impl<T: FixedBits, const FRAC: u32> Fixed<T, FRAC> {
+    /// Converts to `f32`
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// Converts to `f64`
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64() / (1u128 << FRAC) as f64
+    }
+
+    /// Converts from `f32` rounding to nearest, `None` if value is out of range
+    #[inline]
+    pub fn from_f32(value: f32) -> Option<Self> {
+        Self::from_f64(value as f64)
+    }
+
+    /// Converts from `f64` rounding to nearest, `None` if value is out of range
+    #[inline]
+    pub fn from_f64(value: f64) -> Option<Self> {
+        T::from_f64(value * (1u128 << FRAC) as f64).map(Fixed)
+    }
+}
+
+/// Integer types fixed-point numbers can be stored as
+pub trait FixedBits: Copy {
+    /// Converts to `f64`
+    fn to_f64(self) -> f64;
+
+    /// Converts from `f64` rounding to nearest, `None` if value is out of range
+    fn from_f64(value: f64) -> Option<Self>;
+}
+
+macro_rules! impl_fixed_bits {
+    ($($t: ty),*) => {
+        $(
+            impl FixedBits for $t {
+                #[inline]
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                #[inline]
+                fn from_f64(value: f64) -> Option<Self> {
+                    round(value, <$t>::MIN as f64, <$t>::MAX as f64).map(|bits| bits as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_bits!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// Linear scale of raw field value, `value = raw * scale + offset`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale {
+    scale: f64,
+    offset: f64,
+}
+
+impl Scale {
+    /// Creates scale, `scale` must not be zero
+    pub const fn new(scale: f64, offset: f64) -> Self {
+        Scale { scale, offset }
+    }
+
+    /// Converts raw field value to scaled one
+    #[inline]
+    pub fn to_value(&self, raw: i64) -> f64 {
+        raw as f64 * self.scale + self.offset
+    }
+
+    /// Converts scaled value to raw one rounding to nearest, `None` if result is out of `min..=max` range
+    #[inline]
+    pub fn to_raw(&self, value: f64, min: i64, max: i64) -> Option<i64> {
+        round((value - self.offset) / self.scale, min as f64, max as f64).map(|raw| raw as i64)
+    }
+}
+
+/// Rounds half away from zero, `as` casts then truncate the result to the nearest integer,
+/// `None` if truncated result is out of `min..=max` range or value is NaN
+fn round(value: f64, min: f64, max: f64) -> Option<f64> {
+    let value = if value < 0.0 {
+        value - 0.5
+    } else {
+        value + 0.5
+    };
+    if value > min - 1.0 && value < max + 1.0 {
+        Some(value)
+    } else {
+        None
+    }
+}
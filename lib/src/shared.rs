@@ -0,0 +1,115 @@
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+/// Register shared between thread mode and interrupt handlers
+///
+/// Owns register borrowed with its borrow function, so the register stays removed from its holder
+/// and can't be borrowed elsewhere while shared. Access is granted only inside critical section
+///
+/// Instance is `Sync` only if the register is `Send`, which register data holders are,
+/// since the register handed out by its holder has a single owner
+/// ```no_run
+/// # use ral::{ReadableRegister, WritableRegister};
+/// #
+/// # ral::peripheral! {
+/// #     tim2 {
+/// #         base_address: 0x4000_0000,
+/// #         cr1 {
+/// #             offset: 0x0,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 cms[5:2] as u8,
+/// #                 cen[0:1] as bool,
+/// #             }
+/// #         },
+/// #         dier {
+/// #             offset: 0xC,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uie[0:1] as bool,
+/// #             }
+/// #         },
+/// #         sr {
+/// #             offset: 0x10,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uif[0:1] as bool,
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # use ral::Shared;
+/// # use tim2::Dier;
+/// #
+/// static DIER: Shared<Dier> = Shared::new();
+///
+/// # fn main() {
+/// DIER.share(tim2::dier().unwrap()).ok().unwrap();
+/// DIER.lock(|dier| {
+///     dier.read().set_uie().write(); // Thread mode
+/// });
+/// # }
+///
+/// // Marked with `#[interrupt]` in firmware
+/// # #[allow(non_snake_case)]
+/// fn TIM2() {
+///     DIER.lock(|dier| {
+///         dier.read().unset_uie().write(); // Interrupt handler
+///     });
+/// }
+/// ```
+pub struct Shared<RegisterType> {
+    register: Mutex<RefCell<Option<RegisterType>>>,
+}
+
+impl<RegisterType> Shared<RegisterType> {
+    /// Creates empty instance, intended to be used for `static` declarations
+    pub const fn new() -> Self {
+        Shared {
+            register: Mutex::new(RefCell::new(None)),
+        }
+    }
+
+    /// Takes ownership of the register, returns it back if another one is already shared
+    pub fn share(&self, register: RegisterType) -> Result<(), RegisterType> {
+        critical_section::with(|cs| {
+            let mut shared = self.register.borrow(cs).borrow_mut();
+            if shared.is_some() {
+                Err(register)
+            } else {
+                *shared = Some(register);
+                Ok(())
+            }
+        })
+    }
+
+    /// Runs `f` with exclusive access to the register inside critical section,
+    /// returns `None` if no register is shared
+    ///
+    /// Panics on reentrant call from within `f`
+    pub fn lock<F, T>(&self, f: F) -> Option<T>
+    where
+        F: FnOnce(&mut RegisterType) -> T,
+    {
+        critical_section::with(|cs| self.register.borrow(cs).borrow_mut().as_mut().map(f))
+    }
+
+    /// Gives up ownership of the register, dropping returned register returns it to its holder
+    pub fn release(&self) -> Option<RegisterType> {
+        critical_section::with(|cs| self.register.borrow(cs).borrow_mut().take())
+    }
+}
+
+impl<RegisterType> Default for Shared<RegisterType> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
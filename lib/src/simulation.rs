@@ -0,0 +1,299 @@
+use core::cell::RefCell;
+use core::marker::PhantomData;
+use core::mem::{size_of, MaybeUninit};
+use core::ptr::copy_nonoverlapping;
+
+use std::boxed::Box;
+use std::collections::HashMap;
+use std::thread_local;
+
+use crate::{Transport, VolatileCell};
+
+type ReadHook = Box<dyn FnMut(&mut Memory) -> u64>;
+
+type WriteHook = Box<dyn FnMut(&mut Memory, u64)>;
+
+/// Simulated register values as seen by callbacks, unset registers read as `0`
+///
+/// The model isn't locked while callbacks run, so they can access other registers as well
+pub struct Memory {
+    _private: (),
+}
+
+impl Memory {
+    /// Gets value of the register at `address`
+    pub fn get(&self, address: usize) -> u64 {
+        with_model(|model| model.get(address))
+    }
+
+    /// Sets value of the register at `address`
+    pub fn set(&mut self, address: usize, value: u64) {
+        with_model(|model| model.memory.insert(address, value));
+    }
+
+    /// Updates value of the register at `address` with provided function
+    pub fn update<F: FnOnce(u64) -> u64>(&mut self, address: usize, f: F) {
+        let value = f(self.get(address));
+        self.set(address, value);
+    }
+}
+
+#[derive(Default)]
+struct Model {
+    memory: HashMap<usize, u64>,
+    read_hooks: HashMap<usize, ReadHook>,
+    write_hooks: HashMap<usize, WriteHook>,
+}
+
+impl Model {
+    fn get(&self, address: usize) -> u64 {
+        self.memory.get(&address).copied().unwrap_or(0)
+    }
+}
+
+thread_local! {
+    static MODEL: RefCell<Option<Model>> = const { RefCell::new(None) };
+}
+
+/// Host-side simulation of registers, active for the current thread while the instance is alive
+///
+/// While simulation is active, all register reads and writes made by the thread,
+/// including ones of generated crates and [`SimulatedTransport`], are served by the model
+/// instead of the hardware. Split access and byte order are not applied, values are seen as they are
+/// ```
+/// # use ral::{ReadableRegister, WritableRegister};
+/// #
+/// # ral::peripheral! {
+/// #     tim2 {
+/// #         base_address: 0x4000_0000,
+/// #         cr1 {
+/// #             offset: 0x0,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 cms[5:2] as u8,
+/// #                 cen[0:1] as bool,
+/// #             }
+/// #         },
+/// #         dier {
+/// #             offset: 0xC,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uie[0:1] as bool,
+/// #             }
+/// #         },
+/// #         sr {
+/// #             offset: 0x10,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 uif[0:1] as bool,
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # mod driver {
+/// #     use ral::{ReadableRegister, WritableRegister};
+/// #
+/// #     pub fn enable_timer() {
+/// #         crate::tim2::cr1().unwrap().read().set_cen().write();
+/// #     }
+/// # }
+/// #
+/// # use ral::Simulation;
+/// #
+/// # fn main() {
+/// let simulation = Simulation::start();
+/// // Status register reports ready after enable bit is written to control register
+/// simulation.on_write(tim2::BASE_ADDRESS + 0x0, |memory, value| {
+///     memory.set(tim2::BASE_ADDRESS + 0x0, value);
+///     memory.update(tim2::BASE_ADDRESS + 0x10, |status| status | (value & 0x1));
+/// });
+/// driver::enable_timer();
+/// assert_eq!(simulation.peek(tim2::BASE_ADDRESS + 0x10) & 0x1, 0x1);
+/// # }
+/// ```
+pub struct Simulation {
+    thread_bound: PhantomData<*const ()>,
+}
+
+impl Simulation {
+    /// Starts simulation for the current thread with empty model
+    ///
+    /// Panics if simulation is already active for the thread
+    pub fn start() -> Self {
+        MODEL.with(|model| {
+            let mut model = model.borrow_mut();
+            assert!(model.is_none(), "Simulation is already active");
+            *model = Some(Model::default());
+        });
+        Simulation {
+            thread_bound: PhantomData,
+        }
+    }
+
+    /// Attaches callback producing value on each read of the register at `address`,
+    /// by default value stored in [`Memory`] is read
+    pub fn on_read<F>(&self, address: usize, hook: F) -> &Self
+    where
+        F: FnMut(&mut Memory) -> u64 + 'static,
+    {
+        with_model(|model| model.read_hooks.insert(address, Box::new(hook)));
+        self
+    }
+
+    /// Attaches callback consuming value on each write to the register at `address`,
+    /// by default value is stored in [`Memory`]
+    pub fn on_write<F>(&self, address: usize, hook: F) -> &Self
+    where
+        F: FnMut(&mut Memory, u64) + 'static,
+    {
+        with_model(|model| model.write_hooks.insert(address, Box::new(hook)));
+        self
+    }
+
+    /// Gets value stored for the register at `address`, bypassing callbacks
+    pub fn peek(&self, address: usize) -> u64 {
+        with_model(|model| model.get(address))
+    }
+
+    /// Sets value stored for the register at `address`, bypassing callbacks
+    pub fn poke(&self, address: usize, value: u64) -> &Self {
+        with_model(|model| model.memory.insert(address, value));
+        self
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        MODEL.with(|model| model.borrow_mut().take());
+    }
+}
+
+/// [`Transport`] backed by active [`Simulation`], for testing drivers of external devices
+pub struct SimulatedTransport;
+
+impl Transport for SimulatedTransport {
+    type Error = core::convert::Infallible;
+
+    fn read(&mut self, address: usize, width: u32) -> Result<u64, Self::Error> {
+        let value = read(address).expect("Simulation is not active");
+        Ok(value & (u64::MAX >> (64 - width)))
+    }
+
+    fn write(&mut self, address: usize, width: u32, value: u64) -> Result<(), Self::Error> {
+        let value = value & (u64::MAX >> (64 - width));
+        assert!(write(address, value), "Simulation is not active");
+        Ok(())
+    }
+}
+
+/// Loads value of the register from the model, if simulation is active
+#[inline]
+pub(crate) unsafe fn load<ValueType: Copy>(
+    register: *const VolatileCell<ValueType>,
+) -> Option<ValueType> {
+    read(register as usize).map(|value| from_u64(value))
+}
+
+/// Stores value of the register to the model, if simulation is active
+#[inline]
+pub(crate) unsafe fn store<ValueType: Copy>(
+    register: *mut VolatileCell<ValueType>,
+    value: ValueType,
+) -> bool {
+    write(register as usize, to_u64(value))
+}
+
+fn with_model<F: FnOnce(&mut Model) -> T, T>(f: F) -> T {
+    MODEL.with(|model| {
+        f(model
+            .borrow_mut()
+            .as_mut()
+            .expect("Simulation is not active"))
+    })
+}
+
+/// Reads the register, the model isn't borrowed while the callback runs,
+/// and the callback is detached meanwhile, so reads of the same register within it aren't hooked
+fn read(address: usize) -> Option<u64> {
+    let hook = MODEL.with(|model| {
+        model
+            .borrow_mut()
+            .as_mut()
+            .map(|model| model.read_hooks.remove(&address))
+    })?;
+    Some(match hook {
+        Some(mut hook) => {
+            let value = hook(&mut Memory { _private: () });
+            with_model(|model| {
+                model.read_hooks.entry(address).or_insert(hook);
+            });
+            value
+        }
+        None => with_model(|model| model.get(address)),
+    })
+}
+
+/// Writes the register, the model isn't borrowed while the callback runs,
+/// and the callback is detached meanwhile, so writes of the same register within it aren't hooked
+fn write(address: usize, value: u64) -> bool {
+    let hook = MODEL.with(|model| {
+        model
+            .borrow_mut()
+            .as_mut()
+            .map(|model| model.write_hooks.remove(&address))
+    });
+    match hook {
+        Some(Some(mut hook)) => {
+            hook(&mut Memory { _private: () }, value);
+            with_model(|model| {
+                model.write_hooks.entry(address).or_insert(hook);
+            });
+            true
+        }
+        Some(None) => {
+            with_model(|model| model.memory.insert(address, value));
+            true
+        }
+        None => false,
+    }
+}
+
+/// Offset of the value's least significant byte within `u64`
+#[inline]
+fn low_offset<ValueType>() -> usize {
+    if cfg!(target_endian = "little") {
+        0
+    } else {
+        size_of::<u64>() - size_of::<ValueType>()
+    }
+}
+
+#[inline]
+unsafe fn from_u64<ValueType: Copy>(value: u64) -> ValueType {
+    let bytes = value.to_ne_bytes();
+    let mut result = MaybeUninit::<ValueType>::uninit();
+    copy_nonoverlapping(
+        bytes.as_ptr().add(low_offset::<ValueType>()),
+        result.as_mut_ptr() as *mut u8,
+        size_of::<ValueType>(),
+    );
+    result.assume_init()
+}
+
+#[inline]
+unsafe fn to_u64<ValueType: Copy>(value: ValueType) -> u64 {
+    let mut bytes = [0u8; 8];
+    copy_nonoverlapping(
+        &value as *const ValueType as *const u8,
+        bytes.as_mut_ptr().add(low_offset::<ValueType>()),
+        size_of::<ValueType>(),
+    );
+    u64::from_ne_bytes(bytes)
+}
@@ -0,0 +1,190 @@
+use crate::{Cache, WritableRegister};
+
+/// Ordered multi-register write transaction
+///
+/// Registers are staged one by one and written in the same order on commit,
+/// so dependent configuration (e.g. PLL setup before enabling it) is applied in a defined sequence
+/// ```no_run
+/// # pub mod types {
+/// #     #[derive(Clone, Copy)]
+/// #     pub enum PllSource {
+/// #         Hsi = 0,
+/// #         Hse = 1,
+/// #     }
+/// #
+/// #     impl core::convert::TryFrom<PllSource> for u32 {
+/// #         type Error = ();
+/// #
+/// #         fn try_from(value: PllSource) -> Result<Self, Self::Error> {
+/// #             Ok(value as u32)
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # ral::peripheral! {
+/// #     use crate::types::PllSource;
+/// #
+/// #     rcc {
+/// #         base_address: 0x4002_3800,
+/// #         cr {
+/// #             offset: 0x0,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 #[access = "write-only"]
+/// #                 pllon[24:1] as bool,
+/// #             }
+/// #         },
+/// #         pllcfgr {
+/// #             offset: 0x4,
+/// #             value_size: 32,
+/// #             reset_mask: 0xFFFFFFFF,
+/// #             reset_value: 0x0,
+/// #             fields: {
+/// #                 #[access = "write-only"]
+/// #                 pllm[0:6] as u8,
+/// #                 #[access = "write-only"]
+/// #                 pllsrc[22:1] as PllSource,
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # use ral::Transaction;
+/// # use types::PllSource;
+/// #
+/// # fn main() -> Result<(), ()> {
+/// let mut pllcfgr = rcc::pllcfgr().unwrap();
+/// let mut cr = rcc::cr().unwrap();
+/// Transaction::new()
+///     .try_stage(&mut pllcfgr, |r| r.set_pllm(4).set_pllsrc(PllSource::Hse))? // Custom type setter may fail
+///     .stage(&mut cr, |r| r.set_pllon())
+///     .commit_critical(); // Writes `pllcfgr` and then `cr` with interrupts disabled
+/// # Ok(())
+/// # }
+/// ```
+pub struct Transaction<Stages: TransactionStages> {
+    stages: Stages,
+}
+
+impl Transaction<()> {
+    /// Creates empty transaction
+    pub fn new() -> Self {
+        Transaction { stages: () }
+    }
+}
+
+impl Default for Transaction<()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Stages: TransactionStages> Transaction<Stages> {
+    /// Stages new value of the register using infallible setters
+    pub fn stage<'a, RegisterType, F>(
+        self,
+        register: &'a mut RegisterType,
+        f: F,
+    ) -> Transaction<Staged<'a, RegisterType, Stages>>
+    where
+        RegisterType: WritableRegister<RegisterType = RegisterType>,
+        F: FnOnce(&mut RegisterType) -> &mut RegisterType,
+    {
+        let cache = register.save_cache();
+        f(register);
+        Transaction {
+            stages: Staged {
+                register,
+                cache,
+                previous: self.stages,
+            },
+        }
+    }
+
+    /// Stages new value of the register using fallible setters, e.g. ones accepting custom types
+    ///
+    /// On error cache of the register and all previously staged registers is rolled back
+    /// to the state it had before staging, and the transaction is discarded
+    pub fn try_stage<'a, RegisterType, F, E>(
+        mut self,
+        register: &'a mut RegisterType,
+        f: F,
+    ) -> Result<Transaction<Staged<'a, RegisterType, Stages>>, E>
+    where
+        RegisterType: WritableRegister<RegisterType = RegisterType>,
+        F: FnOnce(&mut RegisterType) -> Result<&mut RegisterType, E>,
+    {
+        let cache = register.save_cache();
+        if let Err(err) = f(register) {
+            register.restore_cache(cache);
+            self.stages.rollback();
+            return Err(err);
+        }
+        Ok(Transaction {
+            stages: Staged {
+                register,
+                cache,
+                previous: self.stages,
+            },
+        })
+    }
+
+    /// Rolls back cache of all staged registers and discards the transaction
+    pub fn rollback(mut self) {
+        self.stages.rollback();
+    }
+
+    /// Writes all staged registers in the order they were staged
+    pub fn commit(mut self) {
+        self.stages.commit();
+    }
+
+    /// Writes all staged registers in the order they were staged inside critical section
+    pub fn commit_critical(self) {
+        critical_section::with(|_| self.commit());
+    }
+}
+
+/// Registers staged in a transaction, implemented for `()` and [`Staged`] chains
+pub trait TransactionStages {
+    /// Writes staged registers in the order they were staged
+    fn commit(&mut self);
+
+    /// Restores cache of staged registers
+    fn rollback(&mut self);
+}
+
+impl TransactionStages for () {
+    fn commit(&mut self) {}
+
+    fn rollback(&mut self) {}
+}
+
+/// Register staged in a transaction along with previously staged ones
+pub struct Staged<'a, RegisterType, Previous>
+where
+    RegisterType: WritableRegister<RegisterType = RegisterType>,
+    Previous: TransactionStages,
+{
+    register: &'a mut RegisterType,
+    cache: Cache<RegisterType::ValueType>,
+    previous: Previous,
+}
+
+impl<'a, RegisterType, Previous> TransactionStages for Staged<'a, RegisterType, Previous>
+where
+    RegisterType: WritableRegister<RegisterType = RegisterType>,
+    Previous: TransactionStages,
+{
+    fn commit(&mut self) {
+        self.previous.commit();
+        self.register.write();
+    }
+
+    fn rollback(&mut self) {
+        self.register.restore_cache(self.cache);
+        self.previous.rollback();
+    }
+}
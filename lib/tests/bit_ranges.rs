@@ -0,0 +1,44 @@
+use ral::{Introspect, ReadableRegister, WritableRegister};
+
+#[macro_use]
+mod common;
+
+mapped_register! {
+    ctl {
+        offset: 0x0,
+        value_size: 16,
+        reset_mask: 0xFFFF,
+        reset_value: 0x0,
+        fields: {
+            high[15..=8] as u8,
+            flag[7..=7] as bool,
+            pins[3..=2; 2 step 2] as u8,
+            low[0:2] as u8,
+        }
+    }
+}
+
+#[test]
+fn test_msb_lsb_ranges() {
+    use ctl::Ctl;
+
+    let mut memory: u16 = 0x0003;
+    common::map(&mut memory);
+
+    let mut ctl = ctl::ctl().unwrap();
+    ctl.read().set_high(0xAB).set_flag().set_pins(1, 3).write();
+    assert_eq!(ctl.get_high(), 0xAB);
+    assert_eq!(ctl.get_pins1(), 3);
+    assert_eq!(ctl.get_low(), 3);
+    drop(ctl);
+    assert_eq!(memory, 0xAB83 | (3 << 4));
+
+    assert_eq!((Ctl::HIGH_OFFSET, Ctl::HIGH_MASK), (8, 0xFF00));
+    assert_eq!((Ctl::FLAG_OFFSET, Ctl::FLAG_MASK), (7, 0x0080));
+    assert_eq!(Ctl::PINS_COUNT, 2);
+    let fields = Ctl::METADATA.fields;
+    assert_eq!((fields[0].offset, fields[0].width), (8, 8));
+    assert_eq!((fields[1].offset, fields[1].width), (7, 1));
+    assert_eq!((fields[2].offset, fields[2].width), (2, 2));
+    assert_eq!((fields[3].offset, fields[3].width), (4, 2));
+}
@@ -0,0 +1,53 @@
+mod peripheral {
+    pub const BASE_ADDRESS: usize = 0x4000_0000;
+
+    pub mod reg0 {
+        use ral::register;
+
+        register! {
+            reg0 {
+                offset: 0x4,
+                value_size: 32,
+                reset_mask: 0xFFFFFFFF,
+                reset_value: 0x0
+            }
+        }
+    }
+
+    pub mod ch {
+        use ral::register;
+
+        register! {
+            ch {
+                offset: 0x20,
+                value_size: 32,
+                count: 4,
+                stride: 0x8,
+                reset_mask: 0xFFFFFFFF,
+                reset_value: 0x0
+            }
+        }
+    }
+}
+
+#[test]
+fn test_borrow_is_exclusive() {
+    let register = peripheral::reg0::reg0();
+    assert!(register.is_some());
+    assert!(peripheral::reg0::reg0().is_none());
+    drop(register);
+    assert!(peripheral::reg0::reg0().is_some());
+}
+
+#[test]
+fn test_array_index_out_of_bounds() {
+    use ral::BorrowErrorKind;
+
+    assert!(peripheral::ch::ch(4).is_none());
+    let error = peripheral::ch::try_ch(4).err().unwrap();
+    assert_eq!(error.kind(), BorrowErrorKind::OutOfBounds);
+    let register = peripheral::ch::try_ch(3).unwrap();
+    let error = peripheral::ch::try_ch(3).err().unwrap();
+    assert_eq!(error.kind(), BorrowErrorKind::Borrowed);
+    drop(register);
+}
@@ -0,0 +1,44 @@
+use ral::ReadableRegister;
+
+#[macro_use]
+mod common;
+
+mapped_register! {
+    cfg {
+        offset: 0x0,
+        value_size: 32,
+        reset_mask: 0xFFFFFFFF,
+        reset_value: 0x0000_0300,
+        fields: {
+            #[modified_write_values = "oneToClear"]
+            ovr[0:1; 4 step 1] as bool,
+            lvl[4:2] as enum Lvl { A = 0, B = 1, C = 2, D = 3 },
+            prescaler[8:4] as u8,
+            trim[12:4] as i8,
+        }
+    }
+}
+
+#[test]
+fn test_value_builders() {
+    use cfg::{Cfg, Lvl};
+
+    let mut memory: u32 = 0xFFFF_FFFF;
+    common::map(&mut memory);
+
+    let mut value = Cfg::build();
+    value.set_lvl(Lvl::C).set_trim(-2).unwrap();
+    assert_eq!(value.get_bits(), 0x0000_E320);
+    let mut cfg = cfg::cfg().unwrap();
+    cfg.write_value(value);
+    assert_eq!(cfg.get_bits(), 0x0000_E320);
+    // Register isn't read, flags not set explicitly are written as no-op zeros
+    assert_eq!(memory, 0x0000_E320);
+
+    let mut value = Cfg::build_zeroed();
+    value.set_prescaler(0x5).clear_ovr(2);
+    assert_eq!(value.get_bits(), 0x0000_0504);
+    cfg.write_value(value);
+    drop(cfg);
+    assert_eq!(memory, 0x0000_0504);
+}
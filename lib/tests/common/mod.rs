@@ -0,0 +1,34 @@
+use ral::RuntimeBase;
+
+/// Runtime base of registers declared with `mapped_register!`
+pub static BASE: RuntimeBase = RuntimeBase::new();
+
+/// Maps registers declared with `mapped_register!` to host memory
+pub fn map<T>(memory: &mut T) {
+    BASE.set(memory as *mut T as usize);
+}
+
+/// Declares module with register of the same name relative to [`BASE`], optionally preceded by single
+/// use expression, the rest of register definition is passed as is
+macro_rules! mapped_register {
+    ($name: ident { $($definition: tt)* }) => {
+        mapped_register! { @uses [] $name { $($definition)* } }
+    };
+    (use $($path: ident)::+; $name: ident { $($definition: tt)* }) => {
+        mapped_register! { @uses [use $($path)::+;] $name { $($definition)* } }
+    };
+    (@uses [$($uses: tt)*] $name: ident { $($definition: tt)* }) => {
+        mod $name {
+            use ral::register;
+
+            register! {
+                $($uses)*
+
+                $name {
+                    runtime_base: crate::common::BASE,
+                    $($definition)*
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,68 @@
+use ral::{Introspect, ReadableRegister, WritableRegister};
+
+#[macro_use]
+mod common;
+
+mapped_register! {
+    moder {
+        offset: 0x0,
+        value_size: 32,
+        reset_mask: 0xFFFFFFFF,
+        reset_value: 0x0,
+        fields: {
+            #[doc = "Pin mode"]
+            mode[0:2; 16 step 2] as u8,
+        }
+    }
+}
+
+mapped_register! {
+    flags {
+        offset: 0x4,
+        value_size: 32,
+        reset_mask: 0xFFFFFFFF,
+        reset_value: 0x0,
+        fields: {
+            #[modified_write_values = "oneToClear"]
+            ch[0:1; 4 step 8] as bool,
+            lvl[4:2; 3 step 8] as enum Lvl { A = 0, B = 1, C = 2, D = 3 },
+        }
+    }
+}
+
+#[test]
+fn test_field_arrays() {
+    use flags::{Flags, Lvl};
+    use moder::Moder;
+
+    let mut memory: [u32; 2] = [0x0000_0000, 0x0000_0101];
+    common::map(&mut memory);
+
+    let mut moder = moder::moder().unwrap();
+    moder.read().set_mode(3, 2).set_mode15(1).write();
+    assert_eq!(moder.get_mode(3), 2);
+    assert_eq!(moder.get_mode15(), 1);
+    assert_eq!(moder.get_mode(15), moder.get_mode15());
+    assert_eq!((Moder::MODE_COUNT, Moder::MODE_STEP), (16, 2));
+    assert_eq!(Moder::MODE_7_OFFSET, 14);
+    assert_eq!(Moder::METADATA.fields.len(), 16);
+    assert_eq!(Moder::METADATA.fields[5].name, "mode5");
+
+    let mut flags = flags::flags().unwrap();
+    assert!(flags.read().is_ch_set(0));
+    assert!(flags.is_ch1_set());
+    assert!(!flags.is_ch_set(2));
+    flags.set_lvl(1, Lvl::D).clear_ch(1).write();
+    assert_eq!(flags.get_lvl(1), Lvl::D);
+    assert_eq!(flags.get_lvl1(), Lvl::D);
+    assert!(Flags::LVL_1_D.mask() != 0);
+    drop((moder, flags));
+    // `ch0` flag is written as no-op zero, only `ch1` one is explicitly cleared
+    assert_eq!(memory, [(2 << 6) | (1 << 30), 0x0000_3100]);
+}
+
+#[test]
+#[should_panic(expected = "Field index out of range")]
+fn test_field_array_index_out_of_range() {
+    moder::Moder::build_zeroed().set_mode(16, 1);
+}
@@ -0,0 +1,102 @@
+use ral::{Fixed, ReadableRegister, Scale, WritableRegister};
+
+#[macro_use]
+mod common;
+
+/// Host memory the registers are mapped to, aligned for 64-bit register
+#[repr(align(8))]
+struct Memory([u32; 4]);
+
+mod types {
+    use core::convert::TryFrom;
+
+    /// Custom type sharing its name with `ral::Fixed`
+    #[derive(Debug, PartialEq)]
+    pub struct Fixed(pub u8);
+
+    impl TryFrom<u32> for Fixed {
+        type Error = ();
+
+        fn try_from(value: u32) -> Result<Self, Self::Error> {
+            Ok(Fixed(value as u8))
+        }
+    }
+
+    impl From<Fixed> for u32 {
+        fn from(value: Fixed) -> Self {
+            value.0 as u32
+        }
+    }
+
+    /// Custom type sharing its name with `ral::Scale`
+    pub type Scale = u8;
+}
+
+mapped_register! {
+    use crate::types::Fixed;
+
+    gain {
+        offset: 0x0,
+        value_size: 32,
+        reset_mask: 0xFFFFFFFF,
+        reset_value: 0x0,
+        fields: {
+            q[0:12] as Fixed<i16, 8>,
+            uq[12:4] as ral::Fixed<u8, 2>,
+            temp[16:8] as f32 { scale: 0.5, offset: -40 },
+            delta[24:4] as f64 { scale: 0.25, signed: true },
+            custom[28:4] as Fixed,
+        }
+    }
+}
+
+mapped_register! {
+    use crate::types::Scale;
+
+    counter {
+        offset: 0x8,
+        value_size: 64,
+        reset_mask: 0xFFFFFFFFFFFFFFFF,
+        reset_value: 0x0,
+        fields: {
+            ticks[0:63] as f64 { scale: 0.5 },
+            sign[63:1] as Scale,
+        }
+    }
+}
+
+#[test]
+fn test_numeric_fields() {
+    use gain::Gain;
+
+    let mut memory = Memory([0x0000_0E80, 0, 0xFFFF_FFFF, 0xFFFF_FFFF]);
+    common::map(&mut memory);
+
+    let mut gain = gain::gain().unwrap();
+    assert_eq!(gain.read().get_q().to_f32(), -1.5);
+    assert!(gain.set_q(Fixed::from_f32(8.0).unwrap()).is_err());
+    gain.set_q(Fixed::from_f32(-8.0).unwrap()).unwrap();
+    gain.set_uq(Fixed::from_f32(3.75).unwrap()).unwrap();
+    assert!(gain.set_uq(Fixed::from_f32(4.0).unwrap()).is_err());
+    gain.set_temp(25.2).unwrap();
+    assert_eq!(gain.get_temp(), 25.0);
+    assert_eq!(gain.set_temp(-41.0).err(), Some(-41.0));
+    assert_eq!(gain.set_temp(88.0).err(), Some(88.0));
+    assert!(gain.set_temp(f32::NAN).is_err());
+    gain.set_delta(-2.0).unwrap();
+    assert!(gain.set_delta(-2.25).is_err());
+    assert!(gain.set_delta(2.0).is_err());
+    gain.set_custom(types::Fixed(0x9)).unwrap().write();
+    assert_eq!(gain.get_custom(), Ok(types::Fixed(0x9)));
+    assert_eq!(Gain::TEMP_SCALE, Scale::new(0.5, -40.0));
+    drop(gain);
+    assert_eq!(memory.0[0], 0x9882_F800);
+
+    let mut counter = counter::counter().unwrap();
+    assert_eq!(
+        counter.read().get_ticks(),
+        0x7FFF_FFFF_FFFF_FFFFu64 as f64 * 0.5
+    );
+    assert!(counter.set_ticks(-0.5).is_err());
+    assert_eq!(counter.get_sign(), Ok(1));
+}
@@ -0,0 +1,108 @@
+use ral::{peripheral, Register, RuntimeBase};
+
+static BAR: RuntimeBase = RuntimeBase::new();
+
+peripheral! {
+    #[doc = "Timer described in one place"]
+    tmr {
+        base_address: 0x4007_0000,
+        #[doc = "Control register"]
+        ctl {
+            offset: 0x0,
+            value_size: 32,
+            reset_mask: 0xFFFFFFFF,
+            reset_value: 0x5,
+            fields: {
+                en[0:1] as bool,
+                mode[2..=1] as enum Mode { A = 0, B = 1, C = 2, D = 3 },
+            }
+        },
+        #[access = "read-only"]
+        status {
+            offset: 0x4,
+            value_size: 16,
+            reset_mask: 0xFFFF,
+            reset_value: 0x0,
+        },
+        ch {
+            offset: 0x10,
+            value_size: 32,
+            count: 2,
+            stride: 0x4,
+            reset_mask: 0xFFFFFFFF,
+            reset_value: 0x7,
+        },
+        mapped {
+            runtime_base: crate::BAR,
+            offset: 0x8,
+            value_size: 32,
+            reset_mask: 0xFFFFFFFF,
+            reset_value: 0x9,
+        },
+        #[doc = "DMA"]
+        cluster dma {
+            offset: 0x40,
+            dcr {
+                offset: 0x8,
+                value_size: 16,
+                reset_mask: 0xFFFF,
+                reset_value: 0x1234,
+            },
+            cluster inner {
+                offset: 0x10,
+                reg {
+                    offset: 0x0,
+                    value_size: 8,
+                    reset_mask: 0xFF,
+                    reset_value: 0xA5,
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_module_tree() {
+    assert_eq!(tmr::BASE_ADDRESS, 0x4007_0000);
+    assert_eq!(<tmr::Ctl as Register>::ADDRESS, 0x4007_0000);
+    assert_eq!(<tmr::Status as Register>::ADDRESS, 0x4007_0004);
+    assert_eq!(<tmr::Ch as Register>::ADDRESS, 0x4007_0010);
+    assert_eq!(<tmr::Mapped as Register>::ADDRESS, 0x8);
+    assert_eq!(tmr::dma::BASE_ADDRESS, 0x4007_0040);
+    assert_eq!(<tmr::dma::Dcr as Register>::ADDRESS, 0x4007_0048);
+    assert_eq!(<tmr::dma::inner::Reg as Register>::ADDRESS, 0x4007_0050);
+    assert_eq!(tmr::Ctl::MODE_MASK, 0x6);
+
+    let metadata = tmr::METADATA;
+    assert_eq!(metadata.name, "tmr");
+    assert_eq!(metadata.description, Some("Timer described in one place"));
+    assert_eq!(metadata.address, 0x4007_0000);
+    assert_eq!(metadata.registers.len(), 4);
+    assert_eq!(metadata.registers[0].description, Some("Control register"));
+    assert_eq!(metadata.clusters.len(), 1);
+    assert_eq!(metadata.clusters[0].name, "dma");
+    assert_eq!(metadata.clusters[0].description, Some("DMA"));
+    assert_eq!(metadata.clusters[0].clusters[0].address, 0x4007_0050);
+}
+
+#[cfg(feature = "simulation")]
+#[test]
+fn test_reset_all() {
+    let simulation = ral::Simulation::start();
+    simulation.poke(0x4007_0004, 0xAA);
+    BAR.set(0x5000_0000);
+
+    tmr::reset_all().unwrap();
+    assert_eq!(simulation.peek(0x4007_0000), 0x5);
+    assert_eq!(simulation.peek(0x4007_0004), 0xAA);
+    assert_eq!(simulation.peek(0x4007_0010), 0x7);
+    assert_eq!(simulation.peek(0x4007_0014), 0x7);
+    assert_eq!(simulation.peek(0x5000_0008), 0x9);
+    assert_eq!(simulation.peek(0x4007_0048), 0x1234);
+    assert_eq!(simulation.peek(0x4007_0050), 0xA5);
+
+    let ch = tmr::ch(1).unwrap();
+    assert!(tmr::reset_all().is_err());
+    drop(ch);
+    assert!(tmr::try_ch(1).is_ok());
+}
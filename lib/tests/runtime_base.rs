@@ -0,0 +1,94 @@
+use ral::{BorrowErrorKind, ReadableRegister, Register, RuntimeBase, WritableRegister};
+
+static BAR0: RuntimeBase = RuntimeBase::new();
+
+static UNSET: RuntimeBase = RuntimeBase::new();
+
+const DEVICE: usize = 0x4003_0000;
+
+mod blocks {
+    pub mod ctrl {
+        use ral::register;
+
+        register! {
+            ctrl {
+                base: crate::DEVICE + 0x100,
+                offset: 0x4,
+                value_size: 32,
+                reset_mask: 0xFFFFFFFF,
+                reset_value: 0x0
+            }
+        }
+    }
+
+    pub mod bar {
+        use ral::register;
+
+        register! {
+            bar {
+                runtime_base: crate::BAR0,
+                offset: 0x4,
+                value_size: 32,
+                count: 2,
+                stride: 0x4,
+                reset_mask: 0xFFFFFFFF,
+                reset_value: 0x0,
+                fields: { en[0:1] as bool }
+            }
+        }
+    }
+
+    pub mod unmapped {
+        use ral::register;
+
+        register! {
+            unmapped {
+                runtime_base: crate::UNSET,
+                offset: 0x0,
+                value_size: 32,
+                reset_mask: 0xFFFFFFFF,
+                reset_value: 0x0
+            }
+        }
+    }
+}
+
+#[test]
+fn test_const_base() {
+    assert_eq!(blocks::ctrl::Ctrl::ADDRESS, 0x4003_0104);
+    let ctrl = blocks::ctrl::ctrl().unwrap();
+    assert_eq!(ctrl.as_ptr() as usize, 0x4003_0104);
+}
+
+#[test]
+fn test_runtime_base() {
+    use blocks::bar;
+
+    assert_eq!(bar::Bar::ADDRESS, 0x4);
+    let mut memory: [u32; 3] = [0x0, 0x0, 0x0000_0010];
+    BAR0.set(memory.as_mut_ptr() as usize);
+
+    let mut bar1 = bar::bar(1).unwrap();
+    assert_eq!(bar1.as_ptr() as usize, memory.as_ptr() as usize + 0x8);
+    assert_eq!(bar1.read().get_bits(), 0x0000_0010);
+    bar1.set_en().write();
+    drop(bar1);
+    // Bits outside of fields are written as reset value
+    assert_eq!(memory, [0x0, 0x0, 0x0000_0001]);
+}
+
+#[test]
+fn test_unset_runtime_base() {
+    use blocks::unmapped;
+
+    assert!(UNSET.get().is_none());
+    assert!(unmapped::unmapped().is_none());
+    let error = unmapped::try_unmapped().err().unwrap();
+    assert_eq!(error.kind(), BorrowErrorKind::BaseNotSet);
+    assert_eq!(
+        error.to_string(),
+        "Runtime base address of the register is not set"
+    );
+    UNSET.set(0);
+    assert_eq!(UNSET.get(), Some(0));
+}
@@ -0,0 +1,52 @@
+use ral::{ReadableRegister, WritableRegister};
+
+#[macro_use]
+mod common;
+
+mapped_register! {
+    cal {
+        offset: 0x0,
+        value_size: 32,
+        reset_mask: 0xFFFFFFFF,
+        reset_value: 0x0,
+        fields: {
+            trim[4:5] as i8,
+            full[9:8] as i8,
+            wide[17:15] as i64,
+            temp[0:4; 1 step 4] as i16,
+        }
+    }
+}
+
+#[test]
+fn test_signed_fields() {
+    let mut memory: u32 = (0x1F << 4) | (0x80 << 9) | (0x4000 << 17) | 0xD;
+    common::map(&mut memory);
+
+    let mut cal = cal::cal().unwrap();
+    cal.read();
+    assert_eq!(cal.get_trim(), -1);
+    assert_eq!(cal.get_full(), -128);
+    assert_eq!(cal.get_wide(), -16384);
+    assert_eq!(cal.get_temp(0), -3);
+    assert_eq!(cal.get_temp0(), -3);
+
+    cal.set_trim(15).unwrap();
+    assert_eq!(cal.set_trim(16).err(), Some(16));
+    assert_eq!(cal.set_trim(-17).err(), Some(-17));
+    assert_eq!(cal.get_trim(), 15);
+    cal.set_full(127).unwrap();
+    cal.set_wide(16383).unwrap();
+    assert_eq!(cal.set_wide(16384).err(), Some(16384));
+    cal.set_temp(0, 7).unwrap();
+    assert_eq!(cal.set_temp(0, -9).err(), Some(-9));
+    cal.write();
+    drop(cal);
+    assert_eq!(memory, (0x0F << 4) | (0x7F << 9) | (0x3FFF << 17) | 0x7);
+
+    let mut cal = cal::cal().unwrap();
+    cal.read().set_trim(-16).unwrap();
+    cal.write();
+    drop(cal);
+    assert_eq!((memory >> 4) & 0x1F, 0x10);
+}
@@ -1,13 +1,15 @@
-use proc_macro2::Ident;
+use proc_macro2::{Ident, Span};
+use quote::format_ident;
 use syn::parse::{Parse, ParseStream};
 use syn::spanned::Spanned;
-use syn::token::{As, Colon, Comma};
-use syn::{braced, bracketed, Attribute, LitStr, Result};
+use syn::token::{As, Brace, Colon, Comma, DotDotEq, Enum, Semi};
+use syn::{braced, bracketed, Attribute, LitInt, LitStr, Result};
 
-use crate::field_type::_FieldType;
+use crate::field_type::{_Enum, _FieldType, _Scaled};
 use crate::parse::{adjust_ident, attrs_to_meta_map, get_meta};
 use crate::spanned::_Spanned;
 
+#[derive(Clone)]
 pub(super) struct _Field {
     pub(super) name: Ident,
     pub(super) description: Option<LitStr>,
@@ -15,9 +17,39 @@ pub(super) struct _Field {
     pub(super) offset: _Spanned<u32>,
     pub(super) width: _Spanned<u32>,
     pub(super) access: Option<LitStr>,
+    pub(super) modified_write_values: Option<LitStr>,
+    pub(super) dim: Option<_FieldDim>,
+}
+
+/// Field array specification, `[<offset>:<width>; <count> step <step>]`
+#[derive(Clone)]
+pub(super) struct _FieldDim {
+    pub(super) count: _Spanned<u32>,
+    pub(super) step: _Spanned<u32>,
 }
 
 impl _Field {
+    /// Expands field array to named elements `<name>0`, `<name>1`, ..., single field is returned as is
+    pub(super) fn elements(&self) -> Vec<_Field> {
+        match &self.dim {
+            Some(dim) => (0..dim.count.value)
+                .map(|index| {
+                    let offset = self.offset.value + index * dim.step.value;
+                    _Field {
+                        name: format_ident!("{}{}", self.name, index, span = self.name.span()),
+                        offset: _Spanned {
+                            value: offset,
+                            lit: LitInt::new(&offset.to_string(), Span::call_site()),
+                        },
+                        dim: None,
+                        ..self.clone()
+                    }
+                })
+                .collect(),
+            None => vec![self.clone()],
+        }
+    }
+
     pub(super) fn validate(&self, value_size: u32) -> Result<()> {
         let width = self.width.value;
         let offset = self.offset.value;
@@ -48,6 +80,42 @@ impl _Field {
                 ),
             ))?
         }
+        if let _FieldType::Scaled(_Scaled { signed: false, .. }) = &self.ty {
+            if width == 64 {
+                Err(syn::Error::new(
+                    self.width.span(),
+                    "Unsigned scaled field cannot be 64 bits wide, raw value is read as i64",
+                ))?
+            }
+        }
+        if let Some(dim) = &self.dim {
+            if dim.count.value == 0 {
+                Err(syn::Error::new(
+                    dim.count.span(),
+                    "Field array count must be greater than 0",
+                ))?
+            }
+            if dim.step.value < self.width.value {
+                Err(syn::Error::new(
+                    dim.step.span(),
+                    format!(
+                        "Field array step cannot be less than field width [{} < {}]",
+                        dim.step.value, self.width.value
+                    ),
+                ))?
+            }
+            let end =
+                offset as u64 + (dim.count.value as u64 - 1) * dim.step.value as u64 + width as u64;
+            if end > value_size as u64 {
+                Err(syn::Error::new(
+                    dim.count.span(),
+                    format!(
+                        "Field array cannot end beyond value size [{} > {}]",
+                        end, value_size
+                    ),
+                ))?
+            }
+        }
         Ok(())
     }
 }
@@ -60,29 +128,96 @@ impl Parse for _Field {
         let description = get_meta("doc", &mut attrs, name.span()).ok();
         let range;
         let _ = bracketed!(range in input);
-        let offset = _Spanned::from(range.parse())?;
-        let _: Colon = range.parse()?;
-        let width = _Spanned::from(range.parse())?;
+        let (offset, width) = if range.peek2(DotDotEq) {
+            parse_bit_range(&range)?
+        } else {
+            let offset = _Spanned::from(range.parse())?;
+            let _: Colon = range.parse()?;
+            (offset, _Spanned::from(range.parse())?)
+        };
+        if width.value == 0 {
+            Err(syn::Error::new(width.span(), "Field width cannot be zero"))?
+        }
+        let dim = if range.peek(Semi) {
+            let _: Semi = range.parse()?;
+            let count = _Spanned::from(range.parse())?;
+            let step: Ident = range.parse()?;
+            if step != "step" {
+                Err(syn::Error::new(step.span(), "Expected 'step'"))?
+            }
+            let step = _Spanned::from(range.parse())?;
+            Some(_FieldDim { count, step })
+        } else {
+            None
+        };
         let _: As = input.parse()?;
-        let ty = input.parse()?;
+        let ty = if input.peek(Enum) {
+            _FieldType::Enum(input.parse::<_Enum>()?.validate(&width)?)
+        } else {
+            let ty = input.parse()?;
+            if input.peek(Brace) {
+                _FieldType::Scaled(_Scaled::parse(ty, input)?)
+            } else {
+                _FieldType::from(ty, width.value, width.span())?
+            }
+        };
         let access = get_meta("access", &mut attrs, name.span()).ok();
+        let modified_write_values = get_meta("modified_write_values", &mut attrs, name.span())
+            .ok()
+            .map(validate_modified_write_values)
+            .transpose()?;
         if !attrs.is_empty() {
             Err(syn::Error::new(
                 name.span(),
-                format!("Unexpected attributes specified, ony 'doc' and 'access' expected"),
+                "Unexpected attributes specified, ony 'doc', 'access' and 'modified_write_values' expected",
             ))?
         }
         Ok(_Field {
             name,
             description,
-            ty: _FieldType::from(ty, width.value, width.span())?,
+            ty,
             offset,
             width,
             access,
+            modified_write_values,
+            dim,
         })
     }
 }
 
+/// Parses datasheet style `<msb>..=<lsb>` bit range into offset and width
+fn parse_bit_range(input: ParseStream) -> Result<(_Spanned<u32>, _Spanned<u32>)> {
+    let msb: _Spanned<u32> = _Spanned::from(input.parse())?;
+    let _: DotDotEq = input.parse()?;
+    let lsb: _Spanned<u32> = _Spanned::from(input.parse())?;
+    if msb.value < lsb.value {
+        Err(syn::Error::new(
+            msb.span(),
+            format!(
+                "Most significant bit cannot be less than least significant bit [{} < {}], bit range is specified as [<msb>..=<lsb>], e.g. [{}..={}]",
+                msb.value, lsb.value, lsb.value, msb.value
+            ),
+        ))?
+    }
+    let width = msb.value - lsb.value + 1;
+    let width = _Spanned {
+        value: width,
+        lit: LitInt::new(&width.to_string(), msb.span()),
+    };
+    Ok((lsb, width))
+}
+
+fn validate_modified_write_values(modified_write_values: LitStr) -> Result<LitStr> {
+    match modified_write_values.value().as_str() {
+        "oneToClear" | "oneToSet" | "oneToToggle" | "zeroToClear" | "zeroToSet"
+        | "zeroToToggle" | "clear" | "set" | "modify" => Ok(modified_write_values),
+        value => Err(syn::Error::new(
+            modified_write_values.span(),
+            format!("Unsupported modified write values {}", value),
+        )),
+    }
+}
+
 pub(super) struct _Fields(Option<Vec<_Field>>);
 
 impl _Fields {
@@ -90,12 +225,47 @@ impl _Fields {
         _Fields(None)
     }
 
+    pub(super) fn iter(&self) -> std::slice::Iter<'_, _Field> {
+        self.0.as_deref().unwrap_or(&[]).iter()
+    }
+
+    /// Fields with field arrays expanded to their elements
+    pub(super) fn elements(&self) -> Vec<_Field> {
+        self.iter().flat_map(_Field::elements).collect()
+    }
+
     pub(super) fn validate(&self, value_size: u32) -> Result<()> {
         if let Some(fields) = &self.0 {
             for field in fields {
                 field.validate(value_size)?;
             }
         }
+        let elements = self.elements();
+        for (index, field) in elements.iter().enumerate() {
+            let bits = field.offset.value..field.offset.value + field.width.value;
+            let overlapped = elements[..index].iter().find(|other| {
+                bits.start < other.offset.value + other.width.value && other.offset.value < bits.end
+            });
+            if let Some(other) = overlapped {
+                let mut error = syn::Error::new(
+                    field.name.span(),
+                    format!(
+                        "Field {} [{}:{}] overlaps field {} [{}:{}]",
+                        field.name,
+                        field.offset.value,
+                        field.width.value,
+                        other.name,
+                        other.offset.value,
+                        other.width.value
+                    ),
+                );
+                error.combine(syn::Error::new(
+                    other.name.span(),
+                    format!("Field {} is declared here", other.name),
+                ));
+                Err(error)?
+            }
+        }
         Ok(())
     }
 }
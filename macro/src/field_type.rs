@@ -1,27 +1,64 @@
-use proc_macro2::Span;
-use syn::{Result, TypePath};
+use std::collections::HashSet;
 
+use proc_macro2::{Ident, Span};
+use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::token::{Colon, Comma, Enum, Eq, Sub};
+use syn::{
+    braced, Attribute, Expr, ExprLit, GenericArgument, Lit, LitBool, LitFloat, LitInt, LitStr,
+    PathArguments, Result, Type, TypePath,
+};
+
+use crate::parse::{attrs_to_meta_map, get_meta};
+use crate::spanned::_Spanned;
+
+#[derive(Clone)]
 pub(super) enum _FieldType {
     Bool(TypePath),
     Primitive(TypePath),
+    /// Two's complement signed integer and its size in bits
+    Signed(TypePath, u32),
+    Fixed(_Fixed),
+    Scaled(_Scaled),
     Custom(TypePath),
+    Enum(_Enum),
 }
 
 impl _FieldType {
     pub(super) fn from(ty: TypePath, width: u32, width_span: Span) -> Result<_FieldType> {
+        if is_fixed(&ty) {
+            return Ok(_FieldType::Fixed(_Fixed::from(ty, width, width_span)?));
+        }
         if let Some(ty_name) = ty.path.get_ident() {
             match ty_name.to_string().as_str() {
                 "bool" => {
                     if width != 1 {
+                        Err(syn::Error::new(width_span, "Field size must be 1 for bool"))
+                    } else {
+                        Ok(_FieldType::Bool(ty))
+                    }
+                }
+                "u8" | "u16" | "u32" | "u64" => Ok(_FieldType::Primitive(ty)),
+                ty_name @ ("i8" | "i16" | "i32" | "i64") => {
+                    let bits = ty_name[1..].parse().unwrap();
+                    if width > bits {
                         Err(syn::Error::new(
                             width_span,
-                            format!("Field size must be 1 for bool"),
+                            format!(
+                                "Field size cannot be more than {} bits for {}",
+                                bits, ty_name
+                            ),
                         ))
                     } else {
-                        Ok(_FieldType::Bool(ty))
+                        Ok(_FieldType::Signed(ty, bits))
                     }
                 }
-                "u8" | "u16" | "u32" | "u64" => Ok(_FieldType::Primitive(ty)),
+                "f32" | "f64" => Ok(_FieldType::Scaled(_Scaled {
+                    ty,
+                    scale: 1.0,
+                    offset: 0.0,
+                    signed: false,
+                })),
                 _ => Ok(_FieldType::Custom(ty)),
             }
         } else {
@@ -29,3 +66,279 @@ impl _FieldType {
         }
     }
 }
+
+/// Fixed-point number `Fixed<<raw type>, <fractional bits>>`, raw type is one of `i8..i64` or `u8..u64`
+#[derive(Clone)]
+pub(super) struct _Fixed {
+    pub(super) ty: TypePath,
+    pub(super) raw: TypePath,
+    pub(super) bits: u32,
+    pub(super) signed: bool,
+}
+
+/// Whether type is `Fixed<...>` or `ral::Fixed`, other types named `Fixed` (including bare `Fixed` without
+/// generic arguments) are custom ones
+fn is_fixed(ty: &TypePath) -> bool {
+    let segments: Vec<String> = ty
+        .path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect();
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+    let generic = matches!(
+        ty.path.segments.last().map(|segment| &segment.arguments),
+        Some(PathArguments::AngleBracketed(_))
+    );
+    ty.qself.is_none()
+        && match segments.as_slice() {
+            ["Fixed"] => generic,
+            ["ral", "Fixed"] => true,
+            _ => false,
+        }
+}
+
+impl _Fixed {
+    fn from(ty: TypePath, width: u32, width_span: Span) -> Result<_Fixed> {
+        let error = || {
+            syn::Error::new(
+                ty.span(),
+                "Fixed-point type must be specified as Fixed<<integer type>, <fractional bits>>",
+            )
+        };
+        let arguments = match &ty.path.segments.last().ok_or_else(error)?.arguments {
+            PathArguments::AngleBracketed(arguments) if arguments.args.len() == 2 => {
+                &arguments.args
+            }
+            _ => Err(error())?,
+        };
+        let raw = match &arguments[0] {
+            GenericArgument::Type(Type::Path(raw)) => raw.clone(),
+            _ => Err(error())?,
+        };
+        let fractional = match &arguments[1] {
+            GenericArgument::Const(Expr::Lit(ExprLit {
+                lit: Lit::Int(fractional),
+                ..
+            })) => fractional,
+            _ => Err(error())?,
+        };
+        let raw_name = raw.path.get_ident().map(Ident::to_string);
+        let (signed, bits) = match raw_name.as_deref() {
+            Some(raw_name @ ("i8" | "i16" | "i32" | "i64")) => {
+                (true, raw_name[1..].parse().unwrap())
+            }
+            Some(raw_name @ ("u8" | "u16" | "u32" | "u64")) => {
+                (false, raw_name[1..].parse().unwrap())
+            }
+            _ => Err(syn::Error::new(
+                raw.span(),
+                "Fixed-point raw type must be one of i8, i16, i32, i64, u8, u16, u32 or u64",
+            ))?,
+        };
+        if fractional.base10_parse::<u32>()? > bits {
+            Err(syn::Error::new(
+                fractional.span(),
+                format!(
+                    "Fractional bits cannot be more than {} bits of raw type",
+                    bits
+                ),
+            ))?
+        }
+        if width > bits {
+            Err(syn::Error::new(
+                width_span,
+                format!("Field size cannot be more than {} bits of raw type", bits),
+            ))?
+        }
+        let mut ty = ty;
+        if ty.path.segments.len() == 1 {
+            // Generated code doesn't import `Fixed`, so it's always referred to via `ral::`
+            ty.path
+                .segments
+                .insert(0, Ident::new("ral", ty.span()).into());
+        }
+        Ok(_Fixed {
+            ty,
+            raw,
+            bits,
+            signed,
+        })
+    }
+}
+
+/// Linearly scaled field `f32 { scale: <scale>, offset: <offset>, signed: <bool> }`, `f64` is supported as well,
+/// `value = raw * scale + offset`, `signed` specifies whether raw value is two's complement
+#[derive(Clone)]
+pub(super) struct _Scaled {
+    pub(super) ty: TypePath,
+    pub(super) scale: f64,
+    pub(super) offset: f64,
+    pub(super) signed: bool,
+}
+
+impl _Scaled {
+    pub(super) fn parse(ty: TypePath, input: ParseStream) -> Result<_Scaled> {
+        let ty_name = ty.path.get_ident().map(Ident::to_string);
+        if ty_name.as_deref() != Some("f32") && ty_name.as_deref() != Some("f64") {
+            Err(syn::Error::new(
+                ty.span(),
+                "Scale can be specified only for f32 and f64 fields",
+            ))?
+        }
+        let mut scaled = _Scaled {
+            ty,
+            scale: 1.0,
+            offset: 0.0,
+            signed: false,
+        };
+        let content;
+        let _ = braced!(content in input);
+        while !content.is_empty() {
+            let key: Ident = content.parse()?;
+            let _: Colon = content.parse()?;
+            match key.to_string().as_str() {
+                "scale" => {
+                    let (scale, span) = parse_number(&content)?;
+                    if scale == 0.0 || !scale.is_finite() {
+                        Err(syn::Error::new(
+                            span,
+                            "Scale must be finite non-zero number",
+                        ))?
+                    }
+                    scaled.scale = scale;
+                }
+                "offset" => scaled.offset = parse_number(&content)?.0,
+                "signed" => scaled.signed = content.parse::<LitBool>()?.value,
+                key_name => Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "Found unexpected key {}, only 'scale', 'offset' and 'signed' expected",
+                        key_name
+                    ),
+                ))?,
+            }
+            if content.is_empty() {
+                break;
+            }
+            let _: Comma = content.parse()?;
+        }
+        Ok(scaled)
+    }
+}
+
+/// Parses optionally negative integer or float literal
+fn parse_number(input: ParseStream) -> Result<(f64, Span)> {
+    let negative = input.parse::<Option<Sub>>()?.is_some();
+    let lookahead = input.lookahead1();
+    let (value, span) = if lookahead.peek(LitFloat) {
+        let lit: LitFloat = input.parse()?;
+        (lit.base10_parse::<f64>()?, lit.span())
+    } else if lookahead.peek(LitInt) {
+        let lit: LitInt = input.parse()?;
+        (lit.base10_parse::<f64>()?, lit.span())
+    } else {
+        Err(lookahead.error())?
+    };
+    Ok((if negative { -value } else { value }, span))
+}
+
+/// Enum declared inline in field specification, `enum <Name> { <Variant> = <value>, ... }`
+#[derive(Clone)]
+pub(super) struct _Enum {
+    pub(super) name: Ident,
+    pub(super) variants: Vec<_Variant>,
+    pub(super) exhaustive: bool,
+}
+
+#[derive(Clone)]
+pub(super) struct _Variant {
+    pub(super) name: Ident,
+    pub(super) description: Option<LitStr>,
+    pub(super) value: _Spanned<u64>,
+}
+
+impl _Enum {
+    pub(super) fn validate(mut self, width: &_Spanned<u32>) -> Result<_Enum> {
+        if self.variants.is_empty() {
+            Err(syn::Error::new(
+                self.name.span(),
+                "Enum must have at least one variant",
+            ))?
+        }
+        if width.value > 64 {
+            Err(syn::Error::new(
+                width.span(),
+                format!(
+                    "Enum field width cannot be more than 64 bits [{} > 64]",
+                    width.value
+                ),
+            ))?
+        }
+        let max = 2u128.pow(width.value) - 1;
+        let mut values = HashSet::new();
+        for variant in &self.variants {
+            if variant.value.value as u128 > max {
+                Err(syn::Error::new(
+                    variant.value.lit.span(),
+                    format!(
+                        "Variant value doesn't fit field width [{} > {}]",
+                        variant.value.value, max
+                    ),
+                ))?
+            }
+            if !values.insert(variant.value.value) {
+                Err(syn::Error::new(
+                    variant.value.lit.span(),
+                    format!("Duplicate variant value {}", variant.value.value),
+                ))?
+            }
+        }
+        self.exhaustive = values.len() as u128 == max + 1;
+        Ok(self)
+    }
+}
+
+impl Parse for _Enum {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let _: Enum = input.parse()?;
+        let name: Ident = input.parse()?;
+        let content;
+        let _ = braced!(content in input);
+        let mut variants = Vec::new();
+        while !content.is_empty() {
+            variants.push(content.parse::<_Variant>()?);
+            if content.is_empty() {
+                break;
+            }
+            let _: Comma = content.parse()?;
+        }
+        Ok(_Enum {
+            name,
+            variants,
+            exhaustive: false,
+        })
+    }
+}
+
+impl Parse for _Variant {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let mut attrs = attrs_to_meta_map(attrs)?;
+        let name: Ident = input.parse()?;
+        let description = get_meta("doc", &mut attrs, name.span()).ok();
+        if !attrs.is_empty() {
+            Err(syn::Error::new(
+                name.span(),
+                "Unexpected attributes specified, only 'doc' expected",
+            ))?
+        }
+        let _: Eq = input.parse()?;
+        let value = _Spanned::from(input.parse())?;
+        Ok(_Variant {
+            name,
+            description,
+            value,
+        })
+    }
+}
@@ -1,15 +1,17 @@
-//! Provides macro with DSL describing register
+//! Provides macros with DSL describing register and peripheral
 //! For documentation refer to [ral](https://docs.rs/ral) crate
 use proc_macro::TokenStream;
 
 use syn::parse_macro_input;
 
+use crate::peripheral::_PeripheralWithUses;
 use crate::register::{_Register, _RegisterWithUses};
 use crate::uses::_Uses;
 
 mod field;
 mod field_type;
 mod parse;
+mod peripheral;
 mod register;
 mod render;
 mod spanned;
@@ -23,6 +25,14 @@ pub fn register(item: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| TokenStream::from(err.to_compile_error()))
 }
 
+/// Macro expanding into peripheral module with its registers and clusters
+#[proc_macro]
+pub fn peripheral(item: TokenStream) -> TokenStream {
+    render::render_peripheral_with_uses(parse_macro_input!(item as _PeripheralWithUses))
+        .map(TokenStream::from)
+        .unwrap_or_else(|err| TokenStream::from(err.to_compile_error()))
+}
+
 /// Macro expanding into required uses, might be useful when multiple registers to be defined in the same module
 #[proc_macro]
 pub fn register_uses(item: TokenStream) -> TokenStream {
@@ -0,0 +1,145 @@
+use std::collections::HashSet;
+
+use syn::parse::{Parse, ParseStream, Result};
+use syn::token::{Colon, Comma};
+use syn::{braced, Attribute, Ident, LitInt, LitStr};
+
+use crate::parse::{attrs_to_meta_map, get_meta};
+use crate::register::_Register;
+use crate::uses::_Uses;
+
+pub(super) struct _PeripheralWithUses(pub(super) _Uses, pub(super) _Peripheral);
+
+impl Parse for _PeripheralWithUses {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let uses = input.parse::<_Uses>()?;
+        let peripheral = input.parse::<_Peripheral>()?;
+        Ok(_PeripheralWithUses(uses, peripheral))
+    }
+}
+
+pub(super) struct _Peripheral {
+    pub(super) name: Ident,
+    pub(super) description: Option<LitStr>,
+    pub(super) base_address: LitInt,
+    pub(super) children: Vec<_Child>,
+}
+
+pub(super) struct _Cluster {
+    pub(super) name: Ident,
+    pub(super) description: Option<LitStr>,
+    pub(super) offset: LitInt,
+    pub(super) children: Vec<_Child>,
+}
+
+/// Register or cluster of peripheral or cluster, kept in declaration order
+pub(super) enum _Child {
+    Cluster(_Cluster),
+    Register(Box<_Register>),
+}
+
+impl Parse for _Peripheral {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let name: Ident = input.parse()?;
+        let description = parse_description(attrs, &name)?;
+        let (base_address, children) = parse_body(input, &name, "base_address")?;
+        Ok(_Peripheral {
+            name,
+            description,
+            base_address,
+            children,
+        })
+    }
+}
+
+impl Parse for _Cluster {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let _: Ident = input.parse()?;
+        let name: Ident = input.parse()?;
+        let description = parse_description(attrs, &name)?;
+        let (offset, children) = parse_body(input, &name, "offset")?;
+        Ok(_Cluster {
+            name,
+            description,
+            offset,
+            children,
+        })
+    }
+}
+
+impl Parse for _Child {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let fork = input.fork();
+        fork.call(Attribute::parse_outer)?;
+        let is_cluster = fork.peek2(Ident) && fork.parse::<Ident>()? == "cluster";
+        if is_cluster {
+            Ok(_Child::Cluster(input.parse()?))
+        } else {
+            Ok(_Child::Register(Box::new(input.parse()?)))
+        }
+    }
+}
+
+fn parse_description(attrs: Vec<Attribute>, name: &Ident) -> Result<Option<LitStr>> {
+    let mut attrs = attrs_to_meta_map(attrs)?;
+    let description = get_meta("doc", &mut attrs, name.span()).ok();
+    if !attrs.is_empty() {
+        Err(syn::Error::new(
+            name.span(),
+            "Unexpected attributes specified, only 'doc' expected",
+        ))?
+    }
+    Ok(description)
+}
+
+/// Parses `{ <address key>: <address>, <register or cluster>, ... }` body of peripheral or cluster
+fn parse_body(
+    input: ParseStream,
+    name: &Ident,
+    address_key: &str,
+) -> Result<(LitInt, Vec<_Child>)> {
+    let content;
+    let _ = braced!(content in input);
+    let mut address = Err(syn::Error::new(
+        name.span(),
+        format!("{} is not specified", address_key),
+    ));
+    let mut children = Vec::new();
+    let mut names = HashSet::new();
+    while !content.is_empty() {
+        if content.peek(Ident) && content.peek2(Colon) {
+            let key: Ident = content.parse()?;
+            let _: Colon = content.parse()?;
+            if key != address_key {
+                Err(syn::Error::new(
+                    key.span(),
+                    format!(
+                        "Found unexpected key {}, only '{}' expected",
+                        key, address_key
+                    ),
+                ))?
+            }
+            address = content.parse();
+        } else {
+            let child: _Child = content.parse()?;
+            let child_name = match &child {
+                _Child::Cluster(cluster) => &cluster.name,
+                _Child::Register(register) => &register.name,
+            };
+            if !names.insert(child_name.to_string()) {
+                Err(syn::Error::new(
+                    child_name.span(),
+                    format!("Duplicate register or cluster name {}", child_name),
+                ))?
+            }
+            children.push(child);
+        }
+        if content.is_empty() {
+            break;
+        }
+        let _: Comma = content.parse()?;
+    }
+    Ok((address?, children))
+}
@@ -1,7 +1,7 @@
 use syn::parse::{Parse, ParseStream, Result};
 use syn::spanned::Spanned;
 use syn::token::{Colon, Comma};
-use syn::{braced, Attribute, Ident, LitInt, LitStr};
+use syn::{braced, Attribute, Expr, Ident, LitInt, LitStr, Path};
 
 use crate::field::_Fields;
 use crate::parse::{attrs_to_meta_map, get_meta};
@@ -26,9 +26,47 @@ pub(super) struct _Register {
     pub(super) reset_mask: LitInt,
     pub(super) reset_value: LitInt,
     pub(super) access: Option<LitStr>,
+    pub(super) access_width: Option<_Spanned<u32>>,
+    pub(super) access_order: Option<LitStr>,
+    pub(super) byte_order: Option<LitStr>,
+    pub(super) reserved_bits: Option<LitStr>,
+    pub(super) interface: Option<LitStr>,
+    pub(super) dim: Option<_Dim>,
+    pub(super) base: Option<_Base>,
     pub(super) fields: _Fields,
 }
 
+/// Register array dimension, `count` registers `stride` bytes apart
+pub(super) struct _Dim {
+    pub(super) count: _Spanned<usize>,
+    pub(super) stride: _Spanned<u32>,
+}
+
+/// Source of register base address, `super::BASE_ADDRESS` is used if not specified
+pub(super) enum _Base {
+    /// Constant expression evaluating to base address
+    Const(Expr),
+    /// Path to `static` `ral::RuntimeBase` the base address is set to at runtime
+    Runtime(Path),
+}
+
+impl _Register {
+    /// Whether register is written by `reset_all()`, read-only and bus-backed ones aren't
+    pub(super) fn is_resettable(&self) -> bool {
+        let read_only =
+            |access: Option<&LitStr>| access.map(LitStr::value).as_deref() == Some("read-only");
+        let is_bus = self
+            .interface
+            .as_ref()
+            .map(|interface| interface.value() == "bus")
+            .unwrap_or(false);
+        let mut fields = self.fields.iter().peekable();
+        let writable = fields.peek().is_none()
+            || fields.any(|field| !read_only(field.access.as_ref().or(self.access.as_ref())));
+        !is_bus && !read_only(self.access.as_ref()) && writable
+    }
+}
+
 impl Parse for _Register {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut attrs = input.call(Attribute::parse_outer)?;
@@ -39,28 +77,46 @@ impl Parse for _Register {
         let mut attrs = attrs_to_meta_map(attrs)?;
         let description = get_meta("doc", &mut attrs, name.span()).ok();
         let access = get_meta("access", &mut attrs, name.span()).ok();
+        let byte_order = get_meta("byte_order", &mut attrs, name.span())
+            .ok()
+            .map(validate_byte_order)
+            .transpose()?;
+        let reserved_bits = get_meta("reserved_bits", &mut attrs, name.span())
+            .ok()
+            .map(validate_reserved_bits)
+            .transpose()?;
+        let interface = get_meta("interface", &mut attrs, name.span())
+            .ok()
+            .map(validate_interface)
+            .transpose()?;
         if !attrs.is_empty() {
             Err(syn::Error::new(
                 name.span(),
-                format!("Unexpected attributes specified, only 'doc' attribute expected"),
+                "Unexpected attributes specified, only 'doc', 'access', 'byte_order', 'reserved_bits' and 'interface' expected",
             ))?
         }
         let mut offset = Err(syn::Error::new(
             name.span(),
-            format!("Register offset is not specified"),
+            "Register offset is not specified",
         ));
         let mut value_size = Err(syn::Error::new(
             name.span(),
-            format!("Register value size is not specified"),
+            "Register value size is not specified",
         ));
         let mut reset_mask = Err(syn::Error::new(
             name.span(),
-            format!("Register reset mask is not specified"),
+            "Register reset mask is not specified",
         ));
         let mut reset_value = Err(syn::Error::new(
             name.span(),
-            format!("Register reset value is not specified"),
+            "Register reset value is not specified",
         ));
+        let mut access_width = None;
+        let mut access_order = None;
+        let mut count = None;
+        let mut stride = None;
+        let mut base = None;
+        let mut runtime_base = None;
         let mut fields: Result<_Fields> = Ok(_Fields::empty());
         while !content.is_empty() {
             let field_name: Ident = content.parse()?;
@@ -70,6 +126,12 @@ impl Parse for _Register {
                 "value_size" => value_size = content.parse(),
                 "reset_mask" => reset_mask = content.parse(),
                 "reset_value" => reset_value = content.parse(),
+                "access_width" => access_width = Some(_Spanned::from(content.parse())?),
+                "access_order" => access_order = Some(validate_access_order(content.parse()?)?),
+                "count" => count = Some(_Spanned::from(content.parse())?),
+                "stride" => stride = Some(_Spanned::from(content.parse())?),
+                "base" => base = Some((field_name, content.parse()?)),
+                "runtime_base" => runtime_base = Some((field_name, content.parse()?)),
                 "fields" => fields = content.parse(),
                 field => Err(syn::Error::new(
                     field_name.span(),
@@ -82,17 +144,31 @@ impl Parse for _Register {
             let _: Comma = content.parse()?;
         }
         let value_size = _Spanned::from(value_size).and_then(validate_value_size)?;
+        let access_width = access_width
+            .map(|access_width| validate_access_width(access_width, value_size.value))
+            .transpose()?;
+        let dim = validate_dim(&name, count, stride, value_size.value, interface.as_ref())?;
+        let base = validate_base(base, runtime_base, interface.as_ref())?;
         let fields = fields?;
         fields.validate(value_size.value)?;
+        let (reset_mask, reset_value) =
+            validate_reset(reset_mask?, reset_value?, value_size.value)?;
 
         Ok(_Register {
             name,
             description,
             offset: _Spanned::from(offset)?,
             value_size,
-            reset_mask: reset_mask?,
-            reset_value: reset_value?,
+            reset_mask,
+            reset_value,
             access,
+            access_width,
+            access_order,
+            byte_order,
+            reserved_bits,
+            interface,
+            dim,
+            base,
             fields,
         })
     }
@@ -100,7 +176,7 @@ impl Parse for _Register {
 
 fn validate_value_size(value_size: _Spanned<u32>) -> Result<_Spanned<u32>> {
     let value = value_size.value;
-    if value < 8 || value > 64 || (value & (value - 1)) != 0 {
+    if !(8..=64).contains(&value) || (value & (value - 1)) != 0 {
         Err(syn::Error::new(
             value_size.span(),
             format!(
@@ -111,3 +187,174 @@ fn validate_value_size(value_size: _Spanned<u32>) -> Result<_Spanned<u32>> {
     }
     Ok(value_size)
 }
+
+fn validate_reset(
+    reset_mask: LitInt,
+    reset_value: LitInt,
+    value_size: u32,
+) -> Result<(LitInt, LitInt)> {
+    let parse = |lit: &LitInt, name: &str| -> Result<u128> {
+        let value = lit.base10_parse::<u128>()?;
+        if value >> value_size != 0 {
+            Err(syn::Error::new(
+                lit.span(),
+                format!(
+                    "Register {} {:#X} doesn't fit in value size of {} bits",
+                    name, value, value_size
+                ),
+            ))?
+        }
+        Ok(value)
+    };
+    let mask = parse(&reset_mask, "reset mask")?;
+    let value = parse(&reset_value, "reset value")?;
+    if value & !mask != 0 {
+        Err(syn::Error::new(
+            reset_value.span(),
+            format!(
+                "Register reset value sets bits outside of reset mask [{:#X}]",
+                value & !mask
+            ),
+        ))?
+    }
+    Ok((reset_mask, reset_value))
+}
+
+fn validate_dim(
+    name: &Ident,
+    count: Option<_Spanned<usize>>,
+    stride: Option<_Spanned<u32>>,
+    value_size: u32,
+    interface: Option<&LitStr>,
+) -> Result<Option<_Dim>> {
+    let (count, stride) = match (count, stride) {
+        (None, None) => return Ok(None),
+        (Some(count), Some(stride)) => (count, stride),
+        _ => Err(syn::Error::new(
+            name.span(),
+            "Register array requires both count and stride to be specified",
+        ))?,
+    };
+    if count.value == 0 {
+        Err(syn::Error::new(
+            count.span(),
+            "Register array count must be positive",
+        ))?
+    }
+    if stride.value < value_size / 8 {
+        Err(syn::Error::new(
+            stride.span(),
+            format!(
+                "Register array stride cannot be less than register size in bytes [{} < {}]",
+                stride.value,
+                value_size / 8
+            ),
+        ))?
+    }
+    if let Some(interface) = interface.filter(|interface| interface.value() == "bus") {
+        Err(syn::Error::new(
+            interface.span(),
+            "Register arrays are not supported for 'bus' interface",
+        ))?
+    }
+    Ok(Some(_Dim { count, stride }))
+}
+
+fn validate_base(
+    base: Option<(Ident, Expr)>,
+    runtime_base: Option<(Ident, Path)>,
+    interface: Option<&LitStr>,
+) -> Result<Option<_Base>> {
+    match (base, runtime_base) {
+        (None, None) => Ok(None),
+        (Some(_), Some((key, _))) => Err(syn::Error::new(
+            key.span(),
+            "Only one of 'base' and 'runtime_base' can be specified",
+        )),
+        (Some((_, base)), None) => Ok(Some(_Base::Const(base))),
+        (None, Some((key, runtime_base))) => {
+            if interface.map(LitStr::value).as_deref() == Some("bus") {
+                Err(syn::Error::new(
+                    key.span(),
+                    "Runtime base address is not supported for 'bus' interface",
+                ))?
+            }
+            Ok(Some(_Base::Runtime(runtime_base)))
+        }
+    }
+}
+
+fn validate_access_width(access_width: _Spanned<u32>, value_size: u32) -> Result<_Spanned<u32>> {
+    let value = access_width.value;
+    if !(8..=64).contains(&value) || (value & (value - 1)) != 0 {
+        Err(syn::Error::new(
+            access_width.span(),
+            format!(
+                "Unsupported access width value {}, must be 8 <= power of 2 <= 64",
+                value
+            ),
+        ))?
+    }
+    if value > value_size {
+        Err(syn::Error::new(
+            access_width.span(),
+            format!(
+                "Access width cannot be more than value size [{} > {}]",
+                value, value_size
+            ),
+        ))?
+    }
+    Ok(access_width)
+}
+
+fn validate_access_order(access_order: LitStr) -> Result<LitStr> {
+    match access_order.value().as_str() {
+        "low-then-high" | "high-then-low" => Ok(access_order),
+        value => Err(syn::Error::new(
+            access_order.span(),
+            format!(
+                "Unsupported access order {}, must be 'low-then-high' or 'high-then-low'",
+                value
+            ),
+        )),
+    }
+}
+
+fn validate_byte_order(byte_order: LitStr) -> Result<LitStr> {
+    match byte_order.value().as_str() {
+        "native" | "little-endian" | "big-endian" => Ok(byte_order),
+        value => Err(syn::Error::new(
+            byte_order.span(),
+            format!(
+                "Unsupported byte order {}, must be 'native', 'little-endian' or 'big-endian'",
+                value
+            ),
+        )),
+    }
+}
+
+fn validate_reserved_bits(reserved_bits: LitStr) -> Result<LitStr> {
+    match reserved_bits.value().as_str() {
+        "preserve" | "zero" | "reset-value" => Ok(reserved_bits),
+        value => Err(syn::Error::new(
+            reserved_bits.span(),
+            format!(
+                "Unsupported reserved bits policy {}, must be 'preserve', 'zero' or 'reset-value'",
+                value
+            ),
+        )),
+    }
+}
+
+fn validate_interface(interface: LitStr) -> Result<LitStr> {
+    match interface.value().as_str() {
+        "memory-mapped" | "bus" => Ok(interface),
+        value => Err(syn::Error::new(
+            interface.span(),
+            format!(
+                "Unsupported register interface {}, must be 'memory-mapped' or 'bus'",
+                value
+            ),
+        )),
+    }
+}
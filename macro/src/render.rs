@@ -1,13 +1,16 @@
+use std::iter;
+
 use convert_case::{Case, Casing};
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned};
 use syn::parse::Result;
 use syn::spanned::Spanned;
-use syn::{Ident, LitInt, LitStr};
+use syn::{parse_quote, Ident, LitFloat, LitInt, LitStr, Path, TypePath};
 
-use crate::field::{_Field, _Fields};
-use crate::field_type::_FieldType;
-use crate::register::{_Register, _RegisterWithUses};
+use crate::field::{_Field, _FieldDim, _Fields};
+use crate::field_type::{_Enum, _FieldType, _Fixed, _Scaled};
+use crate::peripheral::{_Child, _Cluster, _PeripheralWithUses};
+use crate::register::{_Base, _Dim, _Register, _RegisterWithUses};
 use crate::spanned::_Spanned;
 use crate::uses::_Uses;
 
@@ -24,11 +27,164 @@ pub(super) fn render_register_with_uses(
     })
 }
 
+pub(super) fn render_peripheral_with_uses(
+    peripheral_with_uses: _PeripheralWithUses,
+) -> Result<TokenStream> {
+    let _PeripheralWithUses(uses, peripheral) = peripheral_with_uses;
+    let name = peripheral.name;
+    let description = render_description(&peripheral.description)?;
+    let base_address = peripheral.base_address;
+    let children = render_children(&uses, peripheral.children)?;
+    let metadata_name = LitStr::new(&name.to_string(), name.span());
+    let metadata_description = render_optional_str(&peripheral.description);
+    let Children {
+        items,
+        resets,
+        clusters,
+        registers,
+    } = children;
+    Ok(quote! {
+        #description
+        pub mod #name {
+            pub const BASE_ADDRESS: usize = #base_address;
+
+            #(#items)*
+
+            /// Writes reset values to writable registers in declaration order, skipping read-only
+            /// and bus-backed ones
+            ///
+            /// Fails on the first register borrowed elsewhere, registers written before it stay reset
+            pub fn reset_all() -> Result<(), ral::BorrowError> {
+                #resets
+                Ok(())
+            }
+
+            pub const METADATA: ral::PeripheralMetadata = ral::PeripheralMetadata {
+                name: #metadata_name,
+                description: #metadata_description,
+                address: BASE_ADDRESS,
+                clusters: &[#(#clusters::METADATA),*],
+                registers: &[#(<#registers as ral::Introspect>::METADATA),*],
+            };
+        }
+    })
+}
+
+/// Rendered children of peripheral or cluster
+struct Children {
+    items: Vec<TokenStream>,
+    resets: TokenStream,
+    clusters: Vec<Ident>,
+    registers: Vec<Ident>,
+}
+
+fn render_children(uses: &_Uses, children: Vec<_Child>) -> Result<Children> {
+    let mut items = Vec::new();
+    let mut resets = Vec::new();
+    let mut clusters = Vec::new();
+    let mut registers = Vec::new();
+    let mut writes = false;
+    for child in children {
+        match child {
+            _Child::Cluster(cluster) => {
+                let name = cluster.name.clone();
+                items.push(render_cluster(uses, cluster)?);
+                resets.push(quote! {
+                    #name::reset_all()?;
+                });
+                clusters.push(name);
+            }
+            _Child::Register(register) => {
+                let module = register.name.clone();
+                let name = format_ident!(
+                    "{}",
+                    module.to_string().to_case(Case::UpperCamel),
+                    span = module.span()
+                );
+                let try_method_name = format_ident!("try_{}", module, span = module.span());
+                if register.is_resettable() {
+                    writes = true;
+                    resets.push(match &register.dim {
+                        Some(_) => quote! {
+                            for index in 0..#module::#name::COUNT {
+                                #module::#try_method_name(index)?.reset().write();
+                            }
+                        },
+                        None => quote! {
+                            #module::#try_method_name()?.reset().write();
+                        },
+                    });
+                }
+                let definition =
+                    render_register_with_uses(_RegisterWithUses(uses.clone(), *register))?;
+                items.push(quote! {
+                    mod #module {
+                        #definition
+                    }
+                    pub use #module::*;
+                });
+                registers.push(name);
+            }
+        }
+    }
+    let uses = if writes {
+        quote! { use ral::WritableRegister; }
+    } else {
+        TokenStream::new()
+    };
+    Ok(Children {
+        items,
+        resets: quote! {
+            #uses
+            #(#resets)*
+        },
+        clusters,
+        registers,
+    })
+}
+
+fn render_cluster(uses: &_Uses, cluster: _Cluster) -> Result<TokenStream> {
+    let name = cluster.name;
+    let description = render_description(&cluster.description)?;
+    let offset = cluster.offset;
+    let metadata_name = LitStr::new(&name.to_string(), name.span());
+    let metadata_description = render_optional_str(&cluster.description);
+    let Children {
+        items,
+        resets,
+        clusters,
+        registers,
+    } = render_children(uses, cluster.children)?;
+    Ok(quote! {
+        #description
+        pub mod #name {
+            pub const BASE_ADDRESS: usize = super::BASE_ADDRESS + #offset;
+
+            #(#items)*
+
+            /// Writes reset values to writable registers in declaration order, skipping read-only
+            /// and bus-backed ones
+            ///
+            /// Fails on the first register borrowed elsewhere, registers written before it stay reset
+            pub fn reset_all() -> Result<(), ral::BorrowError> {
+                #resets
+                Ok(())
+            }
+
+            pub const METADATA: ral::ClusterMetadata = ral::ClusterMetadata {
+                name: #metadata_name,
+                description: #metadata_description,
+                address: BASE_ADDRESS,
+                clusters: &[#(#clusters::METADATA),*],
+                registers: &[#(<#registers as ral::Introspect>::METADATA),*],
+            };
+        }
+    })
+}
+
 pub(super) fn render_uses(uses: _Uses) -> TokenStream {
     let _Uses(uses) = uses;
     quote! {
-        use core::sync::atomic::AtomicPtr;
-
         use core::convert::TryFrom;
 
         use ral::{borrow_register, init_register, return_register, value_read, value_write, R, Register, ReadableRegister, VolatileCell, WritableRegister};
@@ -46,11 +202,51 @@ pub(super) fn render_register(register: _Register) -> Result<TokenStream> {
     let method_name = register.name;
     let description = render_description(&register.description)?;
     let offset = register.offset;
+    let (address, runtime_base) = match &register.base {
+        None => (quote! { super::BASE_ADDRESS + #offset }, None),
+        Some(_Base::Const(base)) => (quote! { (#base) + #offset }, None),
+        Some(_Base::Runtime(base)) => (quote! { #offset }, Some(base)),
+    };
     let value_size = register.value_size;
     let value_type = format_ident!("u{}", value_size.value, span = value_size.span());
     let reset_mask = register.reset_mask;
     let value_reset = register.reset_value;
+    let one_to_modify_mask =
+        build_modified_write_values_mask(&register.fields, "oneTo", &value_size);
+    let zero_to_modify_mask =
+        build_modified_write_values_mask(&register.fields, "zeroTo", &value_size);
+    let access_width = render_access_width(&register.access_width, &register.access_order);
+    let byte_order = render_byte_order(&register.byte_order);
     let access = register.access.as_ref();
+    let writable_mask = build_writable_mask(&register.fields, access, &value_size);
+    let reserved_bits = render_reserved_bits(&register.reserved_bits);
+    let metadata = render_metadata(
+        &name,
+        &method_name,
+        &register.description,
+        &value_size,
+        &value_reset,
+        &reset_mask,
+        access,
+        register.dim.as_ref(),
+        &register.fields,
+    );
+    let is_bus = register
+        .interface
+        .as_ref()
+        .map(|interface| interface.value() == "bus")
+        .unwrap_or(false);
+    let builder = match access.map(LitStr::value).as_deref() {
+        Some("read-only") => TokenStream::new(),
+        _ if is_bus => TokenStream::new(),
+        _ => render_builder(
+            &register.fields,
+            &name,
+            &value_type,
+            value_size.value,
+            access,
+        )?,
+    };
     let register_impl = render_impl(
         register.fields,
         &name,
@@ -58,39 +254,265 @@ pub(super) fn render_register(register: _Register) -> Result<TokenStream> {
         value_size.value,
         access,
     )?;
-    let access = render_access(&name, access)?;
+    let (holder, access) = if is_bus {
+        (
+            render_bus_holder(&name, &method_name, &description, &value_type),
+            render_bus_access(&name, access)?,
+        )
+    } else {
+        (
+            match &register.dim {
+                Some(dim) => render_array_holder(
+                    &name,
+                    &method_name,
+                    &description,
+                    &value_type,
+                    runtime_base,
+                    dim,
+                ),
+                None => render_holder(&name, &method_name, &description, &value_type, runtime_base),
+            },
+            render_access(&name, access)?,
+        )
+    };
     Ok(quote! {
-        const REGISTER: AtomicPtr<VolatileCell<<#name as Register>::ValueType>> =
-            init_register!(super::BASE_ADDRESS + #offset, #name);
+        #holder
+
+        impl Register for #name {
+            type RegisterType = Self;
+
+            type ValueType = #value_type;
+
+            const ADDRESS: usize = #address;
+
+            const RESET_MASK: Self::ValueType = #reset_mask;
+
+            const RESET_VALUE: Self::ValueType = #value_reset;
+
+            const WRITABLE_MASK: Self::ValueType = #writable_mask;
+
+            #reserved_bits
+
+            const ONE_TO_MODIFY_MASK: Self::ValueType = #one_to_modify_mask;
+
+            const ZERO_TO_MODIFY_MASK: Self::ValueType = #zero_to_modify_mask;
+
+            #access_width
+
+            #byte_order
+        }
+
+        #metadata
+
+        #access
+
+        #register_impl
+
+        #builder
+    })
+}
+
+fn render_holder(
+    name: &Ident,
+    method_name: &Ident,
+    description: &TokenStream,
+    value_type: &Ident,
+    runtime_base: Option<&Path>,
+) -> TokenStream {
+    let field_values = render_field_values(name, value_type);
+    let try_method_name = format_ident!("try_{}", method_name, span = method_name.span());
+    let holder = render_init_register(quote! { <#name as Register>::ADDRESS }, name, runtime_base);
+    quote! {
+        static REGISTER: ral::RegisterHolder<<#name as Register>::ValueType> = #holder;
 
         #description
+        #[track_caller]
         pub fn #method_name() -> Option<#name> {
             borrow_register(&REGISTER).map(#name)
         }
 
+        #description
+        #[track_caller]
+        pub fn #try_method_name() -> Result<#name, ral::BorrowError> {
+            ral::try_borrow_register(&REGISTER).map(#name)
+        }
+
         pub struct #name(R<#value_type, #name>);
 
         impl Drop for #name {
             fn drop(&mut self) {
-                let #name(register) = self;
-                return_register(&REGISTER, register);
+                return_register(&REGISTER);
             }
         }
 
-        impl Register for #name {
-            type RegisterType = Self;
+        impl #name {
+            /// Gets pointer to the register, e.g. for DMA configuration
+            #[inline]
+            pub fn as_ptr(&self) -> *mut #value_type {
+                self.0.as_ptr() as *mut #value_type
+            }
 
-            type ValueType = #value_type;
+            /// Gets location the register is currently borrowed at,
+            /// requires `borrow-tracking` feature of `ral`
+            pub fn who_holds() -> Option<&'static core::panic::Location<'static>> {
+                REGISTER.who_holds()
+            }
+        }
 
-            const RESET_MASK: Self::ValueType = #reset_mask;
+        #field_values
+    }
+}
 
-            const RESET_VALUE: Self::ValueType = #value_reset;
+/// Renders holder initialization, address is relative to `runtime_base` if specified
+fn render_init_register(
+    address: TokenStream,
+    name: &Ident,
+    runtime_base: Option<&Path>,
+) -> TokenStream {
+    match runtime_base {
+        Some(runtime_base) => quote! { init_register!(#address, #name, #runtime_base) },
+        None => quote! { init_register!(#address, #name) },
+    }
+}
+
+fn render_array_holder(
+    name: &Ident,
+    method_name: &Ident,
+    description: &TokenStream,
+    value_type: &Ident,
+    runtime_base: Option<&Path>,
+    dim: &_Dim,
+) -> TokenStream {
+    let field_values = render_field_values(name, value_type);
+    let try_method_name = format_ident!("try_{}", method_name, span = method_name.span());
+    let at_method_name = format_ident!("{}_at", method_name, span = method_name.span());
+    let count = &dim.count;
+    let stride = &dim.stride;
+    let holders = (0..dim.count.value).map(|index| {
+        render_init_register(
+            quote! { <#name as Register>::ADDRESS + #index * #name::STRIDE },
+            name,
+            runtime_base,
+        )
+    });
+    quote! {
+        static REGISTERS: [ral::RegisterHolder<<#name as Register>::ValueType>; #count] = [#(#holders),*];
+
+        #description
+        #[track_caller]
+        pub fn #method_name(index: usize) -> Option<#name> {
+            match REGISTERS.get(index) {
+                Some(holder) => borrow_register(holder).map(|register| #name(register, index)),
+                None => None,
+            }
         }
 
-        #access
+        #description
+        ///
+        /// Fails with `BorrowErrorKind::OutOfBounds` if `index` is out of bounds
+        #[track_caller]
+        pub fn #try_method_name(index: usize) -> Result<#name, ral::BorrowError> {
+            match REGISTERS.get(index) {
+                Some(holder) => ral::try_borrow_register(holder).map(|register| #name(register, index)),
+                None => Err(ral::BorrowError::out_of_bounds()),
+            }
+        }
 
-        #register_impl
-    })
+        #description
+        ///
+        /// Out of bounds `INDEX` fails compilation
+        #[track_caller]
+        pub fn #at_method_name<const INDEX: usize>() -> Option<#name> {
+            struct Bounds<const INDEX: usize>;
+
+            impl<const INDEX: usize> Bounds<INDEX> {
+                const CHECKED: () = assert!(INDEX < #name::COUNT, "Register index out of bounds");
+            }
+
+            let () = Bounds::<INDEX>::CHECKED;
+            #method_name(INDEX)
+        }
+
+        pub struct #name(R<#value_type, #name>, usize);
+
+        impl Drop for #name {
+            fn drop(&mut self) {
+                return_register(&REGISTERS[self.1]);
+            }
+        }
+
+        impl #name {
+            /// Number of registers in the array
+            pub const COUNT: usize = #count;
+
+            /// Distance between registers of the array in bytes
+            pub const STRIDE: usize = #stride;
+
+            /// Gets index of the register in the array
+            #[inline]
+            pub fn index(&self) -> usize {
+                self.1
+            }
+
+            /// Gets pointer to the register, e.g. for DMA configuration
+            #[inline]
+            pub fn as_ptr(&self) -> *mut #value_type {
+                self.0.as_ptr() as *mut #value_type
+            }
+
+            /// Gets location the register at `index` is currently borrowed at,
+            /// requires `borrow-tracking` feature of `ral`
+            pub fn who_holds(index: usize) -> Option<&'static core::panic::Location<'static>> {
+                REGISTERS.get(index).and_then(ral::RegisterHolder::who_holds)
+            }
+        }
+
+        #field_values
+    }
+}
+
+fn render_bus_holder(
+    name: &Ident,
+    method_name: &Ident,
+    description: &TokenStream,
+    value_type: &Ident,
+) -> TokenStream {
+    let field_values = render_field_values(name, value_type);
+    quote! {
+        #description
+        pub fn #method_name() -> #name {
+            #name(ral::BusR::new(<#name as Register>::ADDRESS))
+        }
+
+        pub struct #name(ral::BusR<#value_type, #name>);
+
+        #field_values
+    }
+}
+
+fn render_field_values(name: &Ident, value_type: &Ident) -> TokenStream {
+    quote! {
+        impl #name {
+            /// Applies field values to cached bits
+            #[inline]
+            pub fn modify(&mut self, value: ral::FieldValue<#value_type, Self>) -> &mut Self {
+                self.0.update_bits(value.mask(), value.value());
+                self
+            }
+
+            /// Checks whether all fields of cached bits have specified values
+            #[inline]
+            pub fn matches_all(&self, value: ral::FieldValue<#value_type, Self>) -> bool {
+                value.matches_all(self.0.get_bits())
+            }
+
+            /// Checks whether any bit set in specified values is set in cached bits
+            #[inline]
+            pub fn matches_any(&self, value: ral::FieldValue<#value_type, Self>) -> bool {
+                value.matches_any(self.0.get_bits())
+            }
+        }
+    }
 }
 
 fn render_description(description: &Option<LitStr>) -> Result<TokenStream> {
@@ -103,6 +525,134 @@ fn render_description(description: &Option<LitStr>) -> Result<TokenStream> {
     }
 }
 
+fn render_access_width(
+    access_width: &Option<_Spanned<u32>>,
+    access_order: &Option<LitStr>,
+) -> TokenStream {
+    let access_width = access_width.as_ref().map(|access_width| {
+        quote! {
+            const ACCESS_WIDTH: u32 = #access_width;
+        }
+    });
+    let access_order = access_order.as_ref().map(|access_order| {
+        let variant = match access_order.value().as_str() {
+            "high-then-low" => format_ident!("HighThenLow", span = access_order.span()),
+            _ => format_ident!("LowThenHigh", span = access_order.span()),
+        };
+        quote! {
+            const ACCESS_ORDER: ral::AccessOrder = ral::AccessOrder::#variant;
+        }
+    });
+    quote! {
+        #access_width
+
+        #access_order
+    }
+}
+
+fn render_byte_order(byte_order: &Option<LitStr>) -> TokenStream {
+    if let Some(byte_order) = byte_order {
+        let variant = match byte_order.value().as_str() {
+            "little-endian" => format_ident!("LittleEndian", span = byte_order.span()),
+            "big-endian" => format_ident!("BigEndian", span = byte_order.span()),
+            _ => format_ident!("Native", span = byte_order.span()),
+        };
+        quote! {
+            const BYTE_ORDER: ral::ByteOrder = ral::ByteOrder::#variant;
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_metadata(
+    name: &Ident,
+    method_name: &Ident,
+    description: &Option<LitStr>,
+    value_size: &_Spanned<u32>,
+    reset_value: &LitInt,
+    reset_mask: &LitInt,
+    access: Option<&LitStr>,
+    dim: Option<&_Dim>,
+    fields: &_Fields,
+) -> TokenStream {
+    let (count, stride) = match dim {
+        Some(_Dim { count, stride }) => (quote! { #count }, quote! { #stride }),
+        None => (quote! { 1 }, quote! { 0 }),
+    };
+    let register_name = LitStr::new(&method_name.to_string(), method_name.span());
+    let description = render_optional_str(description);
+    let register_access = render_access_mode(access);
+    let elements = fields.elements();
+    let fields = elements.iter().map(|field| {
+        let field_name = LitStr::new(&field.name.to_string(), field.name.span());
+        let description = render_optional_str(&field.description);
+        let field_offset = &field.offset;
+        let width = &field.width;
+        let access = render_access_mode(field.access.as_ref().or(access));
+        quote! {
+            ral::FieldMetadata {
+                name: #field_name,
+                description: #description,
+                offset: #field_offset,
+                width: #width,
+                access: #access,
+            }
+        }
+    });
+    quote! {
+        impl ral::Introspect for #name {
+            const METADATA: ral::RegisterMetadata = ral::RegisterMetadata {
+                name: #register_name,
+                description: #description,
+                address: <#name as Register>::ADDRESS,
+                count: #count,
+                stride: #stride,
+                size: #value_size,
+                reset_value: #reset_value as u64,
+                reset_mask: #reset_mask as u64,
+                access: #register_access,
+                fields: &[#(#fields),*],
+            };
+        }
+    }
+}
+
+fn render_optional_str(value: &Option<LitStr>) -> TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+fn render_access_mode(access: Option<&LitStr>) -> TokenStream {
+    let variant = match access.map(|lit_str| lit_str.value()).as_deref() {
+        Some("read-only") => "ReadOnly",
+        Some("write-only") => "WriteOnly",
+        Some("writeOnce") => "WriteOnce",
+        Some("read-writeOnce") => "ReadWriteOnce",
+        _ => "ReadWrite",
+    };
+    let variant = format_ident!("{}", variant);
+    quote! { ral::AccessMode::#variant }
+}
+
+fn render_reserved_bits(reserved_bits: &Option<LitStr>) -> TokenStream {
+    if let Some(reserved_bits) = reserved_bits {
+        let variant = match reserved_bits.value().as_str() {
+            "preserve" => format_ident!("Preserve", span = reserved_bits.span()),
+            "zero" => format_ident!("Zero", span = reserved_bits.span()),
+            _ => format_ident!("ResetValue", span = reserved_bits.span()),
+        };
+        quote! {
+            const RESERVED_BITS: ral::ReservedBits = ral::ReservedBits::#variant;
+        }
+    } else {
+        TokenStream::new()
+    }
+}
+
 fn render_access(name: &Ident, access: Option<&LitStr>) -> Result<TokenStream> {
     let access = access
         .map(|lit_str| lit_str.value())
@@ -122,6 +672,75 @@ fn render_access(name: &Ident, access: Option<&LitStr>) -> Result<TokenStream> {
     }
 }
 
+fn render_bus_access(name: &Ident, access: Option<&LitStr>) -> Result<TokenStream> {
+    let access = access
+        .map(|lit_str| lit_str.value())
+        .unwrap_or(String::from("read-write"));
+    match access.as_str() {
+        "write-only" | "writeOnce" => render_bus_writable(name),
+        "read-only" => render_bus_readable(name),
+        _ => {
+            let readable = render_bus_readable(name)?;
+            let writable = render_bus_writable(name)?;
+            Ok(quote! {
+                #readable
+
+                #writable
+            })
+        }
+    }
+}
+
+fn render_bus_readable(name: &Ident) -> Result<TokenStream> {
+    Ok(quote! {
+        impl ral::ReadableBusRegister for #name {
+            fn get_bits(&self) -> Self::ValueType {
+                self.0.get_bits()
+            }
+
+            fn read<T: ral::Transport>(
+                &mut self,
+                transport: &mut T,
+            ) -> Result<&mut Self::RegisterType, T::Error> {
+                self.0.read(transport)?;
+                Ok(self)
+            }
+        }
+    })
+}
+
+fn render_bus_writable(name: &Ident) -> Result<TokenStream> {
+    Ok(quote! {
+        impl ral::WritableBusRegister for #name {
+            fn set_bits(&mut self, bits: Self::ValueType) -> &mut Self::RegisterType {
+                self.0.set_bits(bits);
+                self
+            }
+
+            fn reset(&mut self) -> &mut Self::RegisterType {
+                self.set_bits(Self::RESET_VALUE)
+            }
+
+            fn write<T: ral::Transport>(
+                &mut self,
+                transport: &mut T,
+            ) -> Result<&mut Self::RegisterType, T::Error> {
+                self.0.write(transport)?;
+                Ok(self)
+            }
+
+            fn save_cache(&self) -> ral::Cache<Self::ValueType> {
+                self.0.save_cache()
+            }
+
+            fn restore_cache(&mut self, cache: ral::Cache<Self::ValueType>) -> &mut Self::RegisterType {
+                self.0.restore_cache(cache);
+                self
+            }
+        }
+    })
+}
+
 fn render_readable(name: &Ident) -> Result<TokenStream> {
     Ok(quote! {
         impl ReadableRegister for #name {
@@ -153,6 +772,15 @@ fn render_writable(name: &Ident) -> Result<TokenStream> {
                 self.0.write();
                 self
             }
+
+            fn save_cache(&self) -> ral::Cache<Self::ValueType> {
+                self.0.save_cache()
+            }
+
+            fn restore_cache(&mut self, cache: ral::Cache<Self::ValueType>) -> &mut Self::RegisterType {
+                self.0.restore_cache(cache);
+                self
+            }
         }
     })
 }
@@ -164,10 +792,21 @@ fn render_impl(
     value_size: u32,
     access: Option<&LitStr>,
 ) -> Result<TokenStream> {
+    let enums = fields
+        .iter()
+        .map(|field| render_field_enum(field, value_type))
+        .collect::<Vec<TokenStream>>();
     let mut methods = Vec::<TokenStream>::new();
     let methods_iter = fields
         .into_iter()
-        .map(|field| render_field(field, &value_type, value_size, access));
+        .flat_map(|field| {
+            let elements = match field.dim {
+                Some(_) => field.elements(),
+                None => Vec::new(),
+            };
+            iter::once(field).chain(elements)
+        })
+        .map(|field| render_field(field, value_type, value_size, access));
     for method in methods_iter {
         methods.push(method?);
     }
@@ -175,6 +814,8 @@ fn render_impl(
         TokenStream::new()
     } else {
         quote! {
+            #(#enums)*
+
             impl #name {
                 #(#methods)*
             }
@@ -182,36 +823,269 @@ fn render_impl(
     })
 }
 
+/// Renders `build()` starting value of the register from scratch and `write_value()` storing it
+fn render_builder(
+    fields: &_Fields,
+    name: &Ident,
+    value_type: &Ident,
+    value_size: u32,
+    access: Option<&LitStr>,
+) -> Result<TokenStream> {
+    let value_name = format_ident!("{}Value", name, span = name.span());
+    let owner = quote! { #name };
+    let mut methods = Vec::<TokenStream>::new();
+    for field in fields.iter().flat_map(|field| {
+        let elements = match field.dim {
+            Some(_) => field.elements(),
+            None => Vec::new(),
+        };
+        iter::once(field.clone()).chain(elements)
+    }) {
+        if field_access(&field, access) != "read-only" {
+            methods.push(render_write(&field, &owner, value_type, value_size)?);
+            methods.push(render_clear(&field, value_size)?);
+        }
+    }
+    let value_doc = format!(
+        "Value of [`{}`] built from scratch, see [`{}::build`]",
+        name, name
+    );
+    Ok(quote! {
+        impl #name {
+            /// Starts building value of the register from reset value, written with `write_value()`
+            #[inline]
+            pub fn build() -> #value_name {
+                #value_name(ral::RegisterValue::new(<Self as Register>::RESET_VALUE))
+            }
+
+            /// Starts building value of the register from zero, written with `write_value()`
+            #[inline]
+            pub fn build_zeroed() -> #value_name {
+                #value_name(ral::RegisterValue::new(0))
+            }
+
+            /// Writes value built from scratch without reading the register first,
+            /// cached value is replaced by the written one
+            #[inline]
+            pub fn write_value(&mut self, value: #value_name) -> &mut Self {
+                self.0.write_value(value.0);
+                self
+            }
+        }
+
+        #[doc = #value_doc]
+        #[derive(Clone, Copy)]
+        pub struct #value_name(ral::RegisterValue<#value_type, #name>);
+
+        impl #value_name {
+            /// Gets bits of the value
+            #[inline]
+            pub fn get_bits(&self) -> #value_type {
+                self.0.get_bits()
+            }
+
+            #(#methods)*
+        }
+    })
+}
+
+fn render_field_enum(field: &_Field, value_type: &Ident) -> TokenStream {
+    let ty = match &field.ty {
+        _FieldType::Enum(ty) => ty,
+        _ => return TokenStream::new(),
+    };
+    let description = field.description.as_ref().map(|description| {
+        quote! {
+            #[doc = #description]
+        }
+    });
+    let name = &ty.name;
+    let variants = ty
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_name = &variant.name;
+            let value = &variant.value;
+            let variant_description = variant.description.as_ref().map(|description| {
+                quote! {
+                    #[doc = #description]
+                }
+            });
+            quote! {
+                #variant_description
+                #variant_name = #value
+            }
+        })
+        .collect::<Vec<TokenStream>>();
+    let conversions = ty
+        .variants
+        .iter()
+        .map(|variant| {
+            let variant_name = &variant.name;
+            let value = &variant.value;
+            quote! {
+                #value => Ok(#name::#variant_name)
+            }
+        })
+        .collect::<Vec<TokenStream>>();
+    quote! {
+        #description
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        #[repr(#value_type)]
+        pub enum #name {
+            #(#variants),*
+        }
+
+        impl TryFrom<#value_type> for #name {
+            type Error = #value_type;
+
+            fn try_from(value: #value_type) -> Result<Self, Self::Error> {
+                match value {
+                    #(#conversions,)*
+                    _ => Err(value),
+                }
+            }
+        }
+
+        impl From<#name> for #value_type {
+            fn from(value: #name) -> Self {
+                value as #value_type
+            }
+        }
+    }
+}
+
 fn render_field(
     field: _Field,
     value_type: &Ident,
     value_size: u32,
     default_access: Option<&LitStr>,
 ) -> Result<TokenStream> {
-    let access = field
-        .access
-        .as_ref()
-        .or(default_access)
-        .map(|lit_str| lit_str.value())
-        .unwrap_or(String::from("read-write"));
-    match access.as_str() {
-        "write-only" | "writeOnce" => render_write(&field, value_type, value_size),
+    let consts = match &field.dim {
+        Some(dim) => render_field_array_consts(&field, dim),
+        None => render_field_consts(&field, value_type, value_size),
+    };
+    let methods = render_field_methods(field, value_type, value_size, default_access)?;
+    Ok(quote! {
+        #consts
+
+        #methods
+    })
+}
+
+fn render_field_consts(field: &_Field, value_type: &Ident, value_size: u32) -> TokenStream {
+    let name = field.name.to_string().to_case(Case::UpperSnake);
+    let mask_name = format_ident!("{}_MASK", name, span = field.name.span());
+    let offset_name = format_ident!("{}_OFFSET", name, span = field.name.span());
+    let field_name = format_ident!("{}", name, span = field.name.span());
+    let offset = &field.offset;
+    let mask = build_mask(&field.width, value_size);
+    let scale = render_scale_const(field);
+    let values = match &field.ty {
+        _FieldType::Bool(_) => {
+            let set_name = format_ident!("{}_SET", name, span = field.name.span());
+            let clear_name = format_ident!("{}_CLEAR", name, span = field.name.span());
+            quote! {
+                pub const #set_name: ral::FieldValue<#value_type, Self> =
+                    ral::FieldValue::new(#mask << #offset, #mask << #offset);
+
+                pub const #clear_name: ral::FieldValue<#value_type, Self> =
+                    ral::FieldValue::new(#mask << #offset, 0);
+            }
+        }
+        _FieldType::Enum(ty) => {
+            let variants = ty.variants.iter().map(|variant| {
+                let variant_name = format_ident!(
+                    "{}_{}",
+                    name,
+                    variant.name.to_string().to_case(Case::UpperSnake),
+                    span = variant.name.span()
+                );
+                let value = &variant.value;
+                quote! {
+                    pub const #variant_name: ral::FieldValue<#value_type, Self> =
+                        ral::FieldValue::new(#mask << #offset, #value << #offset);
+                }
+            });
+            quote! {
+                #(#variants)*
+            }
+        }
+        _ => TokenStream::new(),
+    };
+    quote! {
+        pub const #field_name: ral::Field<#value_type, Self> = ral::Field::new(#mask, #offset);
+
+        pub const #mask_name: #value_type = #mask << #offset;
+
+        pub const #offset_name: u32 = #offset;
+
+        #values
+
+        #scale
+    }
+}
+
+fn render_field_array_consts(field: &_Field, dim: &_FieldDim) -> TokenStream {
+    let name = field.name.to_string().to_case(Case::UpperSnake);
+    let count_name = format_ident!("{}_COUNT", name, span = field.name.span());
+    let step_name = format_ident!("{}_STEP", name, span = field.name.span());
+    let count = &dim.count;
+    let step = &dim.step;
+    let scale = render_scale_const(field);
+    quote! {
+        pub const #count_name: usize = #count;
+
+        pub const #step_name: u32 = #step;
+
+        #scale
+    }
+}
+
+fn render_field_methods(
+    field: _Field,
+    value_type: &Ident,
+    value_size: u32,
+    default_access: Option<&LitStr>,
+) -> Result<TokenStream> {
+    match field_access(&field, default_access).as_str() {
+        "write-only" | "writeOnce" => {
+            let write = render_write(&field, &quote! { Self }, value_type, value_size)?;
+            let clear = render_clear(&field, value_size)?;
+            Ok(quote! {
+                #write
+
+                #clear
+            })
+        }
         "read-only" => render_read(&field, value_type, value_size),
         _ => {
             let read = render_read(&field, value_type, value_size)?;
-            let write = render_write(&field, value_type, value_size)?;
+            let write = render_write(&field, &quote! { Self }, value_type, value_size)?;
+            let clear = render_clear(&field, value_size)?;
             Ok(quote! {
                 #read
 
                 #write
+
+                #clear
             })
         }
     }
 }
 
+fn field_access(field: &_Field, default_access: Option<&LitStr>) -> String {
+    field
+        .access
+        .as_ref()
+        .or(default_access)
+        .map(|lit_str| lit_str.value())
+        .unwrap_or(String::from("read-write"))
+}
+
 fn render_read(field: &_Field, value_type: &Ident, value_size: u32) -> Result<TokenStream> {
     let description = render_description(&field.description)?;
-    let offset = &field.offset;
+    let (index_param, _, prelude, offset) = render_index(field);
     let mask = build_mask(&field.width, value_size);
     match &field.ty {
         _FieldType::Bool(ty) => {
@@ -219,7 +1093,8 @@ fn render_read(field: &_Field, value_type: &Ident, value_size: u32) -> Result<To
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&self) -> #ty {
+                pub fn #method_name(&self, #index_param) -> #ty {
+                    #prelude
                     value_read!(self, #mask, #offset) == 1
                 }
             })
@@ -229,11 +1104,71 @@ fn render_read(field: &_Field, value_type: &Ident, value_size: u32) -> Result<To
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&self) -> #ty {
+                pub fn #method_name(&self, #index_param) -> #ty {
+                    #prelude
                     value_read!(self, #mask, #offset) as #ty
                 }
             })
         }
+        _FieldType::Signed(ty, bits) => {
+            let method_name = format_ident!("get_{}", field.name);
+            let value = render_sign_extension(
+                quote! { value_read!(self, #mask, #offset) },
+                ty,
+                *bits,
+                field.width.value,
+            );
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&self, #index_param) -> #ty {
+                    #prelude
+                    #value
+                }
+            })
+        }
+        _FieldType::Fixed(_Fixed {
+            ty,
+            raw,
+            bits,
+            signed,
+        }) => {
+            let method_name = format_ident!("get_{}", field.name);
+            let value = render_raw(
+                quote! { value_read!(self, #mask, #offset) },
+                raw,
+                *bits,
+                *signed,
+                field.width.value,
+            );
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&self, #index_param) -> #ty {
+                    #prelude
+                    <#ty>::from_bits(#value)
+                }
+            })
+        }
+        _FieldType::Scaled(_Scaled { ty, signed, .. }) => {
+            let method_name = format_ident!("get_{}", field.name);
+            let scale_name = build_scale_name(field);
+            let value = render_raw(
+                quote! { value_read!(self, #mask, #offset) },
+                &parse_quote!(i64),
+                64,
+                *signed,
+                field.width.value,
+            );
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&self, #index_param) -> #ty {
+                    #prelude
+                    Self::#scale_name.to_value(#value) as #ty
+                }
+            })
+        }
         _FieldType::Custom(ty) => {
             let ty_span = ty.span();
             let _ = quote_spanned! {ty_span=>
@@ -243,17 +1178,56 @@ fn render_read(field: &_Field, value_type: &Ident, value_size: u32) -> Result<To
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&self) -> Result<#ty, <#ty as TryFrom<<Self as Register>::ValueType>>::Error> {
+                pub fn #method_name(&self, #index_param) -> Result<#ty, <#ty as TryFrom<<Self as Register>::ValueType>>::Error> {
+                    #prelude
                     <#ty as TryFrom<<Self as Register>::ValueType>>::try_from(value_read!(self, #mask, #offset))
                 }
             })
         }
+        _FieldType::Enum(_Enum {
+            name: ty,
+            exhaustive,
+            ..
+        }) => {
+            let method_name = format_ident!("get_{}", field.name);
+            let read = quote! {
+                <#ty as TryFrom<<Self as Register>::ValueType>>::try_from(value_read!(self, #mask, #offset))
+            };
+            if *exhaustive {
+                Ok(quote! {
+                    #description
+                    #[inline]
+                    pub fn #method_name(&self, #index_param) -> #ty {
+                        #prelude
+                        match #read {
+                            Ok(value) => value,
+                            Err(_) => unreachable!(),
+                        }
+                    }
+                })
+            } else {
+                Ok(quote! {
+                    #description
+                    #[inline]
+                    pub fn #method_name(&self, #index_param) -> Result<#ty, <Self as Register>::ValueType> {
+                        #prelude
+                        #read
+                    }
+                })
+            }
+        }
     }
 }
 
-fn render_write(field: &_Field, value_type: &Ident, value_size: u32) -> Result<TokenStream> {
+/// Renders field setters of `owner`, which is either register itself or value built for it
+fn render_write(
+    field: &_Field,
+    owner: &TokenStream,
+    value_type: &Ident,
+    value_size: u32,
+) -> Result<TokenStream> {
     let description = render_description(&field.description)?;
-    let offset = &field.offset;
+    let (index_param, index_arg, prelude, offset) = render_index(field);
     let mask = build_mask(&field.width, value_size);
     match &field.ty {
         _FieldType::Bool(ty) => {
@@ -263,21 +1237,22 @@ fn render_write(field: &_Field, value_type: &Ident, value_size: u32) -> Result<T
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&mut self, value: #ty) -> &mut Self {
-                    value_write!(self, #mask, #offset, value as <Self as Register>::ValueType);
+                pub fn #method_name(&mut self, #index_param value: #ty) -> &mut Self {
+                    #prelude
+                    value_write!(self, #mask, #offset, value as <#owner as Register>::ValueType);
                     self
                 }
 
                 #description
                 #[inline]
-                pub fn #method_name_set(&mut self) -> &mut Self {
-                    self.#method_name(true)
+                pub fn #method_name_set(&mut self, #index_param) -> &mut Self {
+                    self.#method_name(#index_arg true)
                 }
 
                 #description
                 #[inline]
-                pub fn #method_name_unset(&mut self) -> &mut Self {
-                    self.#method_name(false)
+                pub fn #method_name_unset(&mut self, #index_param) -> &mut Self {
+                    self.#method_name(#index_arg false)
                 }
             })
         }
@@ -286,12 +1261,71 @@ fn render_write(field: &_Field, value_type: &Ident, value_size: u32) -> Result<T
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&mut self, value: #ty) -> &mut Self {
-                    value_write!(self, #mask, #offset, value as <Self as Register>::ValueType);
+                pub fn #method_name(&mut self, #index_param value: #ty) -> &mut Self {
+                    #prelude
+                    value_write!(self, #mask, #offset, value as <#owner as Register>::ValueType);
                     self
                 }
             })
         }
+        _FieldType::Signed(ty, bits) => {
+            let method_name = format_ident!("set_{}", field.name);
+            let value = render_sign_extension(quote! { bits }, ty, *bits, field.width.value);
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&mut self, #index_param value: #ty) -> Result<&mut Self, #ty> {
+                    #prelude
+                    let bits = value as <#owner as Register>::ValueType & #mask;
+                    if #value != value {
+                        return Err(value);
+                    }
+                    value_write!(self, #mask, #offset, bits);
+                    Ok(self)
+                }
+            })
+        }
+        _FieldType::Fixed(_Fixed {
+            ty,
+            raw,
+            bits,
+            signed,
+        }) => {
+            let method_name = format_ident!("set_{}", field.name);
+            let value = render_raw(quote! { bits }, raw, *bits, *signed, field.width.value);
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&mut self, #index_param value: #ty) -> Result<&mut Self, #ty> {
+                    #prelude
+                    let bits = value.to_bits() as <#owner as Register>::ValueType & #mask;
+                    if #value != value.to_bits() {
+                        return Err(value);
+                    }
+                    value_write!(self, #mask, #offset, bits);
+                    Ok(self)
+                }
+            })
+        }
+        _FieldType::Scaled(_Scaled { ty, signed, .. }) => {
+            let method_name = format_ident!("set_{}", field.name);
+            let scale_name = build_scale_name(field);
+            let (min, max) = build_raw_range(*signed, field.width.value);
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&mut self, #index_param value: #ty) -> Result<&mut Self, #ty> {
+                    #prelude
+                    match #owner::#scale_name.to_raw(value as f64, #min, #max) {
+                        Some(raw) => {
+                            value_write!(self, #mask, #offset, raw as <#owner as Register>::ValueType);
+                            Ok(self)
+                        }
+                        None => Err(value),
+                    }
+                }
+            })
+        }
         _FieldType::Custom(ty) => {
             let ty_span = ty.span();
             let _ = quote_spanned! {ty_span=>
@@ -301,15 +1335,221 @@ fn render_write(field: &_Field, value_type: &Ident, value_size: u32) -> Result<T
             Ok(quote! {
                 #description
                 #[inline]
-                pub fn #method_name(&mut self, value: #ty) -> Result<&mut Self, <<Self as Register>::ValueType as TryFrom<#ty>>::Error> {
-                    value_write!(self, #mask, #offset, <<Self as Register>::ValueType as TryFrom<#ty>>::try_from(value)?);
+                pub fn #method_name(&mut self, #index_param value: #ty) -> Result<&mut Self, <<#owner as Register>::ValueType as TryFrom<#ty>>::Error> {
+                    #prelude
+                    value_write!(self, #mask, #offset, <<#owner as Register>::ValueType as TryFrom<#ty>>::try_from(value)?);
                     Ok(self)
                 }
             })
         }
+        _FieldType::Enum(_Enum { name: ty, .. }) => {
+            let method_name = format_ident!("set_{}", field.name);
+            Ok(quote! {
+                #description
+                #[inline]
+                pub fn #method_name(&mut self, #index_param value: #ty) -> &mut Self {
+                    #prelude
+                    value_write!(self, #mask, #offset, <<#owner as Register>::ValueType as From<#ty>>::from(value));
+                    self
+                }
+            })
+        }
+    }
+}
+
+fn render_clear(field: &_Field, value_size: u32) -> Result<TokenStream> {
+    let description = render_description(&field.description)?;
+    let (index_param, _, prelude, offset) = render_index(field);
+    let mask = build_mask(&field.width, value_size);
+    let method_name = format_ident!("clear_{}", field.name);
+    let modified_write_values = field
+        .modified_write_values
+        .as_ref()
+        .map(|lit_str| lit_str.value());
+    match modified_write_values.as_deref() {
+        Some("oneToClear") => Ok(quote! {
+            #description
+            #[inline]
+            pub fn #method_name(&mut self, #index_param) -> &mut Self {
+                #prelude
+                value_write!(self, #mask, #offset, #mask);
+                self
+            }
+        }),
+        Some("zeroToClear") => Ok(quote! {
+            #description
+            #[inline]
+            pub fn #method_name(&mut self, #index_param) -> &mut Self {
+                #prelude
+                value_write!(self, #mask, #offset, 0);
+                self
+            }
+        }),
+        _ => Ok(TokenStream::new()),
+    }
+}
+
+/// Renders conversion of raw field bits to integer type, sign extended if `signed`
+fn render_raw(
+    bits: TokenStream,
+    ty: &TypePath,
+    ty_bits: u32,
+    signed: bool,
+    width: u32,
+) -> TokenStream {
+    if signed {
+        render_sign_extension(bits, ty, ty_bits, width)
+    } else {
+        quote! { (#bits as #ty) }
     }
 }
 
+/// Builds range of raw values of scaled field as `i64` literals
+fn build_raw_range(signed: bool, width: u32) -> (TokenStream, TokenStream) {
+    let (min, max) = if signed {
+        (-(1i128 << (width - 1)), (1i128 << (width - 1)) - 1)
+    } else {
+        (0, (1i128 << width) - 1)
+    };
+    let render = |value: i128| {
+        if value == i64::MIN as i128 {
+            quote! { i64::MIN }
+        } else {
+            let lit = LitInt::new(&format!("{}i64", value.abs()), Span::call_site());
+            if value < 0 {
+                quote! { -#lit }
+            } else {
+                quote! { #lit }
+            }
+        }
+    };
+    (render(min), render(max))
+}
+
+fn build_scale_name(field: &_Field) -> Ident {
+    let name = field.name.to_string().to_case(Case::UpperSnake);
+    format_ident!("{}_SCALE", name, span = field.name.span())
+}
+
+/// Renders scale constant of scaled field
+fn render_scale_const(field: &_Field) -> TokenStream {
+    match &field.ty {
+        _FieldType::Scaled(_Scaled { scale, offset, .. }) => {
+            let scale_name = build_scale_name(field);
+            let render = |value: f64| {
+                let lit = LitFloat::new(&format!("{:?}", value.abs()), Span::call_site());
+                if value < 0.0 {
+                    quote! { -#lit }
+                } else {
+                    quote! { #lit }
+                }
+            };
+            let scale = render(*scale);
+            let offset = render(*offset);
+            quote! {
+                pub const #scale_name: ral::Scale = ral::Scale::new(#scale, #offset);
+            }
+        }
+        _ => TokenStream::new(),
+    }
+}
+
+/// Renders conversion of raw field bits to signed type, extending sign bit of the field
+fn render_sign_extension(
+    bits: TokenStream,
+    ty: &TypePath,
+    ty_bits: u32,
+    width: u32,
+) -> TokenStream {
+    let shift = ty_bits - width;
+    if shift == 0 {
+        quote! { (#bits as #ty) }
+    } else {
+        quote! { (((#bits as #ty) << #shift) >> #shift) }
+    }
+}
+
+/// Renders index parameter, index argument, offset calculation and offset of field accessors,
+/// field array accessors take element index and check it at runtime
+fn render_index(field: &_Field) -> (TokenStream, TokenStream, TokenStream, TokenStream) {
+    let offset = &field.offset;
+    match &field.dim {
+        Some(_FieldDim { count, step }) => (
+            quote! { index: usize, },
+            quote! { index, },
+            quote! {
+                assert!(index < #count, "Field index out of range");
+                let offset = #offset + index as u32 * #step;
+            },
+            quote! { offset },
+        ),
+        None => (
+            TokenStream::new(),
+            TokenStream::new(),
+            TokenStream::new(),
+            quote! { #offset },
+        ),
+    }
+}
+
+fn build_modified_write_values_mask(
+    fields: &_Fields,
+    prefix: &str,
+    value_size: &_Spanned<u32>,
+) -> LitInt {
+    let elements = fields.elements();
+    let fields = elements.iter().filter(|field| {
+        field
+            .modified_write_values
+            .as_ref()
+            .map(|lit_str| lit_str.value().starts_with(prefix))
+            .unwrap_or(false)
+    });
+    build_fields_mask(fields, value_size)
+}
+
+fn build_writable_mask(
+    fields: &_Fields,
+    default_access: Option<&LitStr>,
+    value_size: &_Spanned<u32>,
+) -> LitInt {
+    if fields.iter().next().is_none() {
+        let mask = format!(
+            "{mask:#0width$X}u{bits}",
+            mask = 2u128.pow(value_size.value) - 1,
+            width = ((value_size.value / 4) + 2) as usize,
+            bits = value_size.value
+        );
+        return LitInt::new(mask.as_str(), value_size.span());
+    }
+    let elements = fields.elements();
+    let fields = elements.iter().filter(|field| {
+        field
+            .access
+            .as_ref()
+            .or(default_access)
+            .map(|lit_str| lit_str.value() != "read-only")
+            .unwrap_or(true)
+    });
+    build_fields_mask(fields, value_size)
+}
+
+fn build_fields_mask<'a>(
+    fields: impl Iterator<Item = &'a _Field>,
+    value_size: &_Spanned<u32>,
+) -> LitInt {
+    let mask = fields.fold(0u128, |mask, field| {
+        mask | ((2u128.pow(field.width.value) - 1) << field.offset.value)
+    });
+    let mask = format!(
+        "{mask:#0width$X}u{bits}",
+        mask = mask,
+        width = ((value_size.value / 4) + 2) as usize,
+        bits = value_size.value
+    );
+    LitInt::new(mask.as_str(), value_size.span())
+}
+
 fn build_mask(width: &_Spanned<u32>, value_size: u32) -> LitInt {
     let mask = format!(
         "{mask:#0width$X}u{bits}",
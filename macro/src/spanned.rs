@@ -5,6 +5,7 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::{LitInt, Result};
 
+#[derive(Clone)]
 pub(super) struct _Spanned<T> {
     pub(super) value: T,
     pub(super) lit: LitInt,
@@ -2,6 +2,7 @@ use syn::parse::{Parse, ParseStream};
 use syn::token::Use;
 use syn::{ItemUse, Result};
 
+#[derive(Clone)]
 pub(super) struct _Uses(pub(super) Vec<ItemUse>);
 
 impl Parse for _Uses {